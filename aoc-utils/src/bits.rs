@@ -0,0 +1,326 @@
+//! Fixed-width bit patterns and packed grids of booleans.
+//!
+//! Note that the two types number their bits in opposite directions: in a
+//! [`BitRow`] index 0 is the most significant bit, so that rows read like
+//! binary literals, while in a [`BitGrid`] column 0 is the least significant
+//! bit of its word, so that rows can span several words.
+
+use std::{fmt, ops::BitXor};
+
+/// A row of `width` bits stored in an integer, e.g. a row of lights that can
+/// be on or off.
+///
+/// Index 0 is the leftmost bit, which is stored as the most significant one,
+/// so that `#..#` is `0b1001`. This is the opposite of [`BitGrid`], where
+/// column 0 is the least significant bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitRow<T> {
+    bits: T,
+    width: usize,
+}
+
+impl<T: Copy> BitRow<T> {
+    #[must_use]
+    pub fn bits(self) -> T {
+        self.bits
+    }
+
+    #[must_use]
+    pub fn width(self) -> usize {
+        self.width
+    }
+}
+
+macro_rules! bit_row_impl {
+    ($($t:ty),+) => {
+        $(
+            impl BitRow<$t> {
+                /// A row of `width` bits, all unset.
+                ///
+                /// # Panics
+                ///
+                /// Panics if `width` is larger than the number of bits in the
+                /// integer type.
+                #[must_use]
+                pub fn new(width: usize) -> Self {
+                    Self::from_bits(0, width)
+                }
+
+                /// Wraps `bits` as a row of `width` bits.
+                ///
+                /// # Panics
+                ///
+                /// Panics if `width` is larger than the number of bits in the
+                /// integer type, or if any bit past `width` is set.
+                #[must_use]
+                pub fn from_bits(bits: $t, width: usize) -> Self {
+                    assert!(width <= <$t>::BITS as usize, "width too large");
+                    assert!(
+                        width == <$t>::BITS as usize || bits >> width == 0,
+                        "bits set past the width"
+                    );
+                    Self { bits, width }
+                }
+
+                /// Parses a row like `.##.`, where `#` is a set bit and `.` is
+                /// an unset one. Returns `None` if there are other characters
+                /// or the row doesn't fit in the integer type.
+                #[must_use]
+                pub fn from_hashes_string(s: &str) -> Option<Self> {
+                    if s.len() > <$t>::BITS as usize {
+                        return None;
+                    }
+                    let mut bits: $t = 0;
+                    for c in s.bytes() {
+                        let bit = match c {
+                            b'#' => 1,
+                            b'.' => 0,
+                            _ => return None,
+                        };
+                        bits = bits << 1 | bit;
+                    }
+                    Some(Self {
+                        bits,
+                        width: s.len(),
+                    })
+                }
+
+                fn mask(self, index: usize) -> $t {
+                    assert!(index < self.width, "index out of bounds");
+                    1 << (self.width - 1 - index)
+                }
+
+                /// Whether the bit at `index` is set.
+                ///
+                /// # Panics
+                ///
+                /// Panics if `index` is not smaller than the width.
+                #[must_use]
+                pub fn get(self, index: usize) -> bool {
+                    self.bits & self.mask(index) != 0
+                }
+
+                /// Flips the bit at `index`.
+                ///
+                /// # Panics
+                ///
+                /// Panics if `index` is not smaller than the width.
+                pub fn toggle(&mut self, index: usize) {
+                    self.bits ^= self.mask(index);
+                }
+
+                #[must_use]
+                pub fn count_ones(self) -> u32 {
+                    self.bits.count_ones()
+                }
+
+                /// Iterates over the indices of the set bits, from left to
+                /// right.
+                pub fn iter_set_bits(self) -> impl Iterator<Item = usize> {
+                    (0..self.width).filter(move |&index| self.get(index))
+                }
+            }
+
+            impl BitXor for BitRow<$t> {
+                type Output = Self;
+
+                fn bitxor(self, rhs: Self) -> Self {
+                    assert_eq!(self.width, rhs.width, "rows have different widths");
+                    Self {
+                        bits: self.bits ^ rhs.bits,
+                        width: self.width,
+                    }
+                }
+            }
+
+            impl fmt::Display for BitRow<$t> {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    for index in 0..self.width {
+                        f.write_str(if self.get(index) { "#" } else { "." })?;
+                    }
+                    Ok(())
+                }
+            }
+        )+
+    };
+}
+
+bit_row_impl!(u16, u32, u64);
+
+/// A grid of booleans packed into bits, one row after the other. Each row
+/// starts at a new `u64` word, so rows can be combined word by word.
+///
+/// Column `x` is bit `x % 64` of word `x / 64`, counting from the least
+/// significant bit, so `#..#` is `0b1001` but `##..` is `0b0011`. This is the
+/// opposite of [`BitRow`], where index 0 is the most significant bit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+}
+
+impl BitGrid {
+    /// A grid with all the bits unset.
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(64);
+        Self {
+            words: vec![0; words_per_row * height],
+            width,
+            height,
+            words_per_row,
+        }
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn position(&self, x: usize, y: usize) -> (usize, u64) {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) out of bounds"
+        );
+        (y * self.words_per_row + x / 64, 1 << (x % 64))
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `(x, y)` is out of bounds.
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        let (word, mask) = self.position(x, y);
+        self.words[word] & mask != 0
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `(x, y)` is out of bounds.
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        let (word, mask) = self.position(x, y);
+        if value {
+            self.words[word] |= mask;
+        } else {
+            self.words[word] &= !mask;
+        }
+    }
+
+    /// The number of set bits in the whole grid.
+    #[must_use]
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// The words of row `y`, where bit `x % 64` of word `x / 64` is the cell
+    /// at `x`. Bits past the width are always unset.
+    #[must_use]
+    pub fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    /// Sets row `dst` to the bitwise OR of itself and row `src`.
+    pub fn or_row(&mut self, dst: usize, src: usize) {
+        self.combine_rows(dst, src, |a, b| a | b);
+    }
+
+    /// Sets row `dst` to the bitwise AND of itself and row `src`.
+    pub fn and_row(&mut self, dst: usize, src: usize) {
+        self.combine_rows(dst, src, |a, b| a & b);
+    }
+
+    /// Sets row `dst` to the bitwise XOR of itself and row `src`.
+    pub fn xor_row(&mut self, dst: usize, src: usize) {
+        self.combine_rows(dst, src, |a, b| a ^ b);
+    }
+
+    fn combine_rows(&mut self, dst: usize, src: usize, op: impl Fn(u64, u64) -> u64) {
+        assert!(dst < self.height && src < self.height, "row out of bounds");
+        for i in 0..self.words_per_row {
+            let word = self.words[src * self.words_per_row + i];
+            let target = &mut self.words[dst * self.words_per_row + i];
+            *target = op(*target, word);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_hashes_string() {
+        let row = BitRow::<u16>::from_hashes_string(".###.#").unwrap();
+        assert_eq!(row.bits(), 0b011101);
+        assert_eq!(row.width(), 6);
+        assert_eq!(row.count_ones(), 4);
+        assert_eq!(row.iter_set_bits().collect::<Vec<_>>(), [1, 2, 3, 5]);
+        assert_eq!(row.to_string(), ".###.#");
+        assert_eq!(BitRow::<u16>::from_hashes_string(".#x"), None);
+        assert_eq!(BitRow::<u16>::from_hashes_string(&"#".repeat(17)), None);
+        let row = BitRow::<u64>::from_hashes_string(&"#".repeat(64)).unwrap();
+        assert_eq!(row.bits(), u64::MAX);
+    }
+
+    #[test]
+    fn toggle() {
+        let mut row = BitRow::<u32>::new(4);
+        assert_eq!(row.to_string(), "....");
+        row.toggle(0);
+        row.toggle(2);
+        assert_eq!(row.to_string(), "#.#.");
+        assert!(row.get(2));
+        row.toggle(2);
+        assert!(!row.get(2));
+        let other = BitRow::<u32>::from_bits(0b1100, 4);
+        assert_eq!((row ^ other).to_string(), ".#..");
+    }
+
+    #[test]
+    fn bit_grid() {
+        let mut grid = BitGrid::new(130, 3);
+        assert_eq!(grid.count_ones(), 0);
+        for x in [0, 63, 64, 129] {
+            grid.set(x, 1, true);
+        }
+        grid.set(100, 2, true);
+        assert_eq!(grid.count_ones(), 5);
+        assert!(grid.get(63, 1) && grid.get(64, 1) && grid.get(129, 1));
+        assert!(!grid.get(65, 1) && !grid.get(129, 0));
+        assert_eq!(grid.row(1), [1 << 63 | 1, 1, 1 << 1]);
+        grid.set(63, 1, false);
+        assert!(!grid.get(63, 1));
+        assert_eq!(grid.count_ones(), 4);
+        grid.set(63, 1, false);
+        assert_eq!(grid.count_ones(), 4);
+    }
+
+    #[test]
+    fn bit_grid_rows() {
+        let mut grid = BitGrid::new(70, 3);
+        for x in [1, 2, 65] {
+            grid.set(x, 0, true);
+        }
+        for x in [2, 3, 65, 69] {
+            grid.set(x, 1, true);
+        }
+        grid.or_row(2, 0);
+        grid.or_row(2, 1);
+        assert_eq!(grid.row(2), [0b1110, 1 << 1 | 1 << 5]);
+        grid.and_row(2, 0);
+        assert_eq!(grid.row(2), grid.row(0));
+        grid.xor_row(2, 1);
+        assert_eq!(grid.row(2), [0b1010, 1 << 5]);
+        assert_eq!(grid.count_ones(), 10);
+    }
+}
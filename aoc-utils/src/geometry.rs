@@ -0,0 +1,431 @@
+//! Geometry on the integer plane and in space.
+
+use std::collections::HashMap;
+
+use crate::point::{Point2, Point3};
+
+/// An axis-aligned line segment, including both endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment<T> {
+    pub start: Point2<T>,
+    pub end: Point2<T>,
+}
+
+impl<T: Copy + Ord> Segment<T> {
+    /// # Panics
+    ///
+    /// Panics if the segment is neither horizontal nor vertical.
+    #[must_use]
+    pub fn new(start: Point2<T>, end: Point2<T>) -> Self {
+        assert!(
+            start.x == end.x || start.y == end.y,
+            "segment is not axis-aligned"
+        );
+        Self { start, end }
+    }
+
+    #[must_use]
+    pub fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
+    }
+
+    #[must_use]
+    pub fn is_vertical(&self) -> bool {
+        self.start.x == self.end.x
+    }
+
+    /// The corners of the bounding box, i.e. the endpoints sorted so that
+    /// the first one is the top-left.
+    fn bounds(&self) -> (Point2<T>, Point2<T>) {
+        (
+            Point2::new(self.start.x.min(self.end.x), self.start.y.min(self.end.y)),
+            Point2::new(self.start.x.max(self.end.x), self.start.y.max(self.end.y)),
+        )
+    }
+}
+
+/// Whether two axis-aligned segments have at least one point in common. This
+/// includes crossing, touching at an endpoint, and overlapping when they are
+/// collinear.
+#[must_use]
+pub fn intersects<T: Copy + Ord>(a: &Segment<T>, b: &Segment<T>) -> bool {
+    // an axis-aligned segment is its own bounding box, so the segments
+    // intersect exactly when their bounding boxes do
+    let (a_min, a_max) = a.bounds();
+    let (b_min, b_max) = b.bounds();
+    a_min.x <= b_max.x && b_min.x <= a_max.x && a_min.y <= b_max.y && b_min.y <= a_max.y
+}
+
+/// The area enclosed by a simple polygon with the given vertices, in either
+/// winding order, using the shoelace formula.
+///
+/// The vertices are the corners of the polygon on a continuous plane, so a
+/// unit square has area 1. To count the grid cells covered by a loop of
+/// cells instead, see Pick's theorem: `area + perimeter / 2 + 1`.
+#[must_use]
+pub fn polygon_area(points: &[Point2<i64>]) -> i64 {
+    twice_signed_area(points).abs() / 2
+}
+
+/// The direction in which a loop of points goes around, as seen on a grid
+/// where `y` grows downwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Winding {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// Finds whether the vertices of a simple polygon are listed clockwise or
+/// counterclockwise, from the sign of its area. A degenerate polygon with no
+/// area is reported as counterclockwise.
+#[must_use]
+pub fn winding(points: &[Point2<i64>]) -> Winding {
+    // with y growing downwards, the shoelace formula is positive for
+    // clockwise loops
+    if twice_signed_area(points) > 0 {
+        Winding::Clockwise
+    } else {
+        Winding::CounterClockwise
+    }
+}
+
+fn twice_signed_area(points: &[Point2<i64>]) -> i64 {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum()
+}
+
+/// The length of the boundary of a polygon whose edges are all horizontal
+/// or vertical, with the given vertices.
+#[must_use]
+pub fn polygon_perimeter(points: &[Point2<i64>]) -> i64 {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| (a.x - b.x).abs() + (a.y - b.y).abs())
+        .sum()
+}
+
+/// The indices of the two closest points, and their squared distance, or
+/// `None` if there are fewer than two points. Ties go to the pair found first,
+/// in the order of the slice.
+///
+/// This checks every pair, so it's quadratic in the number of points. A
+/// spatial index could prune most pairs for large inputs.
+#[must_use]
+pub fn closest_pair(points: &[Point3<i64>]) -> Option<(usize, usize, u64)> {
+    let mut closest = None;
+    for (i, &a) in points.iter().enumerate() {
+        for (j, &b) in points.iter().enumerate().skip(i + 1) {
+            let distance = a.squared_distance(b);
+            if closest.is_none_or(|(_, _, min)| distance < min) {
+                closest = Some((i, j, distance));
+            }
+        }
+    }
+    closest
+}
+
+/// An index of points in space, bucketed into cubic cells of a fixed size, to
+/// find the points near a given one without looking at all of them.
+///
+/// Lookups only visit the cells overlapping the search radius, so the cell
+/// size should be in the same ballpark as the radii being searched.
+#[derive(Debug, Clone)]
+pub struct SpatialHash3<T> {
+    cell_size: T,
+    points: Vec<Point3<T>>,
+    cells: HashMap<(T, T, T), Vec<usize>>,
+}
+
+impl<T> SpatialHash3<T> {
+    /// The points in the index, in insertion order.
+    #[must_use]
+    pub fn points(&self) -> &[Point3<T>] {
+        &self.points
+    }
+}
+
+macro_rules! spatial_hash3_impl {
+    ($($t:ty => $u:ty),+) => {
+        $(
+            impl SpatialHash3<$t> {
+                /// # Panics
+                ///
+                /// Panics if `cell_size` is not positive.
+                #[must_use]
+                pub fn new(cell_size: $t) -> Self {
+                    assert!(cell_size > 0, "cell size should be positive");
+                    Self {
+                        cell_size,
+                        points: Vec::new(),
+                        cells: HashMap::new(),
+                    }
+                }
+
+                /// Creates an index of all the `points`, see
+                /// [`SpatialHash3::new`].
+                #[must_use]
+                pub fn with_points(
+                    cell_size: $t,
+                    points: impl IntoIterator<Item = Point3<$t>>,
+                ) -> Self {
+                    let mut index = Self::new(cell_size);
+                    for point in points {
+                        index.insert(point);
+                    }
+                    index
+                }
+
+                fn cell_of(&self, point: Point3<$t>) -> ($t, $t, $t) {
+                    (
+                        point.x.div_euclid(self.cell_size),
+                        point.y.div_euclid(self.cell_size),
+                        point.z.div_euclid(self.cell_size),
+                    )
+                }
+
+                /// Adds a point to the index, returning its index in
+                /// [`SpatialHash3::points`].
+                pub fn insert(&mut self, point: Point3<$t>) -> usize {
+                    let index = self.points.len();
+                    self.points.push(point);
+                    self.cells.entry(self.cell_of(point)).or_default().push(index);
+                    index
+                }
+
+                /// The indices of all the points within Euclidean distance
+                /// `radius` of `point`, including `point` itself if it's in
+                /// the index. The order is unspecified.
+                pub fn neighbors_within(
+                    &self,
+                    point: Point3<$t>,
+                    radius: $u,
+                ) -> impl Iterator<Item = usize> {
+                    let reach = <$t>::try_from(radius).unwrap_or(<$t>::MAX);
+                    let corner = |offset: fn($t, $t) -> $t| {
+                        Point3::new(
+                            offset(point.x, reach),
+                            offset(point.y, reach),
+                            offset(point.z, reach),
+                        )
+                    };
+                    let (x0, y0, z0) = self.cell_of(corner(<$t>::saturating_sub));
+                    let (x1, y1, z1) = self.cell_of(corner(<$t>::saturating_add));
+                    let span = |a: $t, b: $t| (b as i128 - a as i128 + 1).cast_unsigned();
+                    let visited = span(x0, x1)
+                        .saturating_mul(span(y0, y1))
+                        .saturating_mul(span(z0, z1));
+                    // when the radius covers more cells than there are in the
+                    // index, it's quicker to go through the occupied ones
+                    let buckets: Vec<&Vec<usize>> = if visited > self.cells.len() as u128 {
+                        self.cells
+                            .iter()
+                            .filter(|&(&(x, y, z), _)| {
+                                (x0..=x1).contains(&x)
+                                    && (y0..=y1).contains(&y)
+                                    && (z0..=z1).contains(&z)
+                            })
+                            .map(|(_, bucket)| bucket)
+                            .collect()
+                    } else {
+                        (x0..=x1)
+                            .flat_map(|x| {
+                                (y0..=y1).flat_map(move |y| (z0..=z1).map(move |z| (x, y, z)))
+                            })
+                            .filter_map(|cell| self.cells.get(&cell))
+                            .collect()
+                    };
+                    buckets
+                        .into_iter()
+                        .flatten()
+                        .copied()
+                        .filter(move |&i| self.points[i].within_distance(point, radius))
+                }
+            }
+        )+
+    };
+}
+
+spatial_hash3_impl!(i32 => u32, i64 => u64, isize => usize, u32 => u32, u64 => u64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(x1: i64, y1: i64, x2: i64, y2: i64) -> Segment<i64> {
+        Segment::new(Point2::new(x1, y1), Point2::new(x2, y2))
+    }
+
+    #[test]
+    fn crossing() {
+        let horizontal = segment(0, 5, 10, 5);
+        let vertical = segment(3, 0, 3, 8);
+        assert!(intersects(&horizontal, &vertical));
+        assert!(intersects(&vertical, &horizontal));
+        // the direction of the segments doesn't matter
+        assert!(intersects(&segment(10, 5, 0, 5), &segment(3, 8, 3, 0)));
+    }
+
+    #[test]
+    fn touching() {
+        // T junction
+        assert!(intersects(&segment(0, 5, 10, 5), &segment(3, 5, 3, 8)));
+        // corner
+        assert!(intersects(&segment(0, 0, 4, 0), &segment(4, 0, 4, 4)));
+        // end to end
+        assert!(intersects(&segment(0, 0, 4, 0), &segment(4, 0, 9, 0)));
+    }
+
+    #[test]
+    fn parallel() {
+        assert!(intersects(&segment(0, 0, 5, 0), &segment(3, 0, 9, 0)));
+        assert!(intersects(&segment(2, 1, 2, 9), &segment(2, 3, 2, 4)));
+        assert!(!intersects(&segment(0, 0, 5, 0), &segment(0, 1, 5, 1)));
+        assert!(!intersects(&segment(0, 0, 5, 0), &segment(6, 0, 9, 0)));
+    }
+
+    #[test]
+    fn disjoint() {
+        assert!(!intersects(&segment(0, 5, 10, 5), &segment(3, 6, 3, 8)));
+        assert!(!intersects(&segment(0, 5, 2, 5), &segment(3, 0, 3, 8)));
+    }
+
+    fn points(coordinates: &[(i64, i64)]) -> Vec<Point2<i64>> {
+        coordinates
+            .iter()
+            .map(|&(x, y)| Point2::new(x, y))
+            .collect()
+    }
+
+    #[test]
+    fn unit_square() {
+        let square = points(&[(0, 0), (1, 0), (1, 1), (0, 1)]);
+        assert_eq!(polygon_area(&square), 1);
+        assert_eq!(polygon_perimeter(&square), 4);
+        let reversed = square.iter().rev().copied().collect::<Vec<_>>();
+        assert_eq!(polygon_area(&reversed), 1);
+    }
+
+    #[test]
+    fn day9_example_polygon() {
+        let polygon = points(&[
+            (7, 1),
+            (11, 1),
+            (11, 7),
+            (9, 7),
+            (9, 5),
+            (2, 5),
+            (2, 3),
+            (7, 3),
+        ]);
+        assert_eq!(polygon_area(&polygon), 30);
+        assert_eq!(polygon_perimeter(&polygon), 30);
+        // counting the tiles on the boundary too
+        let tiles = polygon_area(&polygon) + polygon_perimeter(&polygon) / 2 + 1;
+        assert_eq!(tiles, 46);
+        assert_eq!(polygon_area(&[]), 0);
+    }
+
+    #[test]
+    fn winding() {
+        // clockwise on screen: right along the top, then down
+        let square = points(&[(0, 0), (2, 0), (2, 2), (0, 2)]);
+        assert_eq!(super::winding(&square), Winding::Clockwise);
+        let reversed = square.iter().rev().copied().collect::<Vec<_>>();
+        assert_eq!(super::winding(&reversed), Winding::CounterClockwise);
+        // a non-convex loop, in both directions and from a different start
+        let mut loop_ = points(&[
+            (7, 1),
+            (11, 1),
+            (11, 7),
+            (9, 7),
+            (9, 5),
+            (2, 5),
+            (2, 3),
+            (7, 3),
+        ]);
+        assert_eq!(super::winding(&loop_), Winding::Clockwise);
+        loop_.rotate_left(3);
+        assert_eq!(super::winding(&loop_), Winding::Clockwise);
+        loop_.reverse();
+        assert_eq!(super::winding(&loop_), Winding::CounterClockwise);
+    }
+
+    #[test]
+    fn closest_pair_day8_example() {
+        let points = [
+            (162, 817, 812),
+            (57, 618, 57),
+            (906, 360, 560),
+            (592, 479, 940),
+            (352, 342, 300),
+            (466, 668, 158),
+            (542, 29, 236),
+            (431, 825, 988),
+            (739, 650, 466),
+            (52, 470, 668),
+            (216, 146, 977),
+            (819, 987, 18),
+            (117, 168, 530),
+            (805, 96, 715),
+            (346, 949, 466),
+            (970, 615, 88),
+            (941, 993, 340),
+            (862, 61, 35),
+            (984, 92, 344),
+            (425, 690, 689),
+        ]
+        .map(Point3::from);
+        assert_eq!(closest_pair(&points), Some((0, 19, 100_427)));
+        assert_eq!(closest_pair(&points[..1]), None);
+        assert_eq!(closest_pair(&[]), None);
+    }
+
+    #[test]
+    fn spatial_hash_matches_brute_force() {
+        // a small pseudo-random cloud, with some points near the origin
+        let mut state = 12345i64;
+        let mut next = || {
+            state = (state * 1_103_515_245 + 12345).rem_euclid(1 << 31);
+            state % 41 - 20
+        };
+        let points = (0..200)
+            .map(|_| Point3::new(next(), next(), next()))
+            .collect::<Vec<_>>();
+        let index = SpatialHash3::<i64>::with_points(4, points.iter().copied());
+        assert_eq!(index.points(), points);
+        for radius in [0, 1, 3, 4, 7, 30] {
+            for &center in points.iter().take(20).chain(&[Point3::new(-25, 0, 25)]) {
+                let mut found = index.neighbors_within(center, radius).collect::<Vec<_>>();
+                found.sort_unstable();
+                let expected = (0..points.len())
+                    .filter(|&i| points[i].within_distance(center, radius))
+                    .collect::<Vec<_>>();
+                assert_eq!(found, expected, "radius {radius} around {center:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn spatial_hash_unsigned() {
+        let mut index = SpatialHash3::<u32>::new(10);
+        let a = index.insert(Point3::new(0, 0, 0));
+        let b = index.insert(Point3::new(3, 4, 0));
+        index.insert(Point3::new(30, 0, 0));
+        // searching near zero doesn't underflow
+        let mut found = index
+            .neighbors_within(Point3::new(1, 1, 0), 5)
+            .collect::<Vec<_>>();
+        found.sort_unstable();
+        assert_eq!(found, [a, b]);
+        assert_eq!(
+            index
+                .neighbors_within(Point3::new(0, 0, 0), u32::MAX)
+                .count(),
+            3
+        );
+    }
+}
@@ -0,0 +1,506 @@
+//! Search algorithms over implicit graphs, where the edges of each node are
+//! produced on demand by a `neighbors` function.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+use crate::utils::{Annotate, AnnotateExt};
+
+/// Finds the cheapest path from `start` to `goal` with the A* algorithm.
+///
+/// `neighbors` yields each node reachable from the given one, together with
+/// the cost of the edge. `heuristic` must be admissible, i.e. it must never
+/// overestimate the cost to reach `goal`, otherwise the path found might not
+/// be the cheapest.
+///
+/// Returns the total cost and the path, including both `start` and `goal`.
+pub fn astar<N, F, I, H>(
+    start: N,
+    goal: &N,
+    mut neighbors: F,
+    heuristic: H,
+) -> Option<(u64, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+    H: Fn(&N) -> u64,
+{
+    let mut costs = HashMap::new();
+    let mut came_from = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    costs.insert(start.clone(), 0);
+    queue.push(Reverse(heuristic(&start).annotate((0, start))));
+    while let Some(Reverse(Annotate {
+        annotation: (cost, node),
+        ..
+    })) = queue.pop()
+    {
+        if node == *goal {
+            let mut path = vec![node];
+            while let Some(previous) = came_from.get(path.last().unwrap()) {
+                path.push(N::clone(previous));
+            }
+            path.reverse();
+            return Some((cost, path));
+        }
+        if costs.get(&node).is_some_and(|&best| best < cost) {
+            // a cheaper path to this node was found after this was queued
+            continue;
+        }
+        for (next, edge_cost) in neighbors(&node) {
+            let next_cost = cost + edge_cost;
+            if costs.get(&next).is_none_or(|&best| next_cost < best) {
+                costs.insert(next.clone(), next_cost);
+                came_from.insert(next.clone(), node.clone());
+                let estimate = next_cost + heuristic(&next);
+                queue.push(Reverse(estimate.annotate((next_cost, next))));
+            }
+        }
+    }
+    None
+}
+
+/// Finds the cheapest path from `start` to `goal` with Dijkstra's algorithm.
+///
+/// This is the same as [`astar`] without a heuristic.
+pub fn dijkstra<N, F, I>(start: N, goal: &N, neighbors: F) -> Option<(u64, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    astar(start, goal, neighbors, |_| 0)
+}
+
+/// Keeps track of the nodes seen by [`breadth_first`].
+trait Visited<N> {
+    /// Marks `node` as visited, returning whether it was new.
+    fn insert(&mut self, node: &N) -> bool;
+}
+
+impl<N: Clone + Eq + Hash> Visited<N> for HashSet<N> {
+    fn insert(&mut self, node: &N) -> bool {
+        HashSet::insert(self, node.clone())
+    }
+}
+
+/// A bitset of the first `SIZE` indices.
+struct DenseVisited<const SIZE: usize>(Vec<u64>);
+
+impl<const SIZE: usize> DenseVisited<SIZE> {
+    fn new() -> Self {
+        Self(vec![0; SIZE.div_ceil(64)])
+    }
+}
+
+impl<N: Copy + Into<usize>, const SIZE: usize> Visited<N> for DenseVisited<SIZE> {
+    fn insert(&mut self, node: &N) -> bool {
+        let index = (*node).into();
+        assert!(index < SIZE, "node index {index} out of bounds ({SIZE})");
+        let (word, bit) = (index / 64, 1 << (index % 64));
+        let new = self.0[word] & bit == 0;
+        self.0[word] |= bit;
+        new
+    }
+}
+
+/// Visits every node reachable from `start` in breadth-first order, stopping
+/// early at `goal`. Returns the number of nodes visited, and the number of
+/// steps to `goal` if it was reached.
+fn breadth_first<N, F, I>(
+    start: N,
+    goal: Option<&N>,
+    mut neighbors: F,
+    visited: &mut impl Visited<N>,
+) -> (usize, Option<u32>)
+where
+    N: Eq,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut queue = VecDeque::new();
+    let mut count = 1;
+    visited.insert(&start);
+    queue.push_back((start, 0));
+    while let Some((node, steps)) = queue.pop_front() {
+        if goal == Some(&node) {
+            return (count, Some(steps));
+        }
+        for next in neighbors(&node) {
+            if visited.insert(&next) {
+                count += 1;
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+    (count, None)
+}
+
+/// Counts the distinct nodes reachable from `start`, including itself.
+pub fn reachable_count<N, F, I>(start: N, neighbors: F) -> usize
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    breadth_first(start, None, neighbors, &mut HashSet::new()).0
+}
+
+/// Same as [`reachable_count`], but faster for nodes that can be used as
+/// indices smaller than `SIZE`, since it doesn't need to hash them.
+///
+/// # Panics
+///
+/// Panics if a reachable node has an index of `SIZE` or more.
+pub fn reachable_count_dense<const SIZE: usize, N, F, I>(start: N, neighbors: F) -> usize
+where
+    N: Copy + Eq + Into<usize>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    breadth_first(start, None, neighbors, &mut DenseVisited::<SIZE>::new()).0
+}
+
+/// The fewest steps to get from `start` to `goal`, where every edge counts as
+/// one step, or `None` if `goal` is not reachable.
+pub fn min_steps_to<N, F, I>(start: N, goal: &N, neighbors: F) -> Option<u32>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    breadth_first(start, Some(goal), neighbors, &mut HashSet::new()).1
+}
+
+/// Same as [`min_steps_to`], but faster for nodes that can be used as indices
+/// smaller than `SIZE`, since it doesn't need to hash them.
+///
+/// # Panics
+///
+/// Panics if a node visited before reaching `goal` has an index of `SIZE` or
+/// more.
+pub fn min_steps_to_dense<const SIZE: usize, N, F, I>(
+    start: N,
+    goal: &N,
+    neighbors: F,
+) -> Option<u32>
+where
+    N: Copy + Eq + Into<usize>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    breadth_first(
+        start,
+        Some(goal),
+        neighbors,
+        &mut DenseVisited::<SIZE>::new(),
+    )
+    .1
+}
+
+/// The fewest steps to get from `start` to `goal`, like [`min_steps_to`], but
+/// with iterative deepening: a depth-first search is repeated with a growing
+/// depth limit, up to `max_depth`. Returns `None` if `goal` can't be reached
+/// within `max_depth` steps.
+///
+/// Nodes are not remembered, so memory only grows with the depth, at the cost
+/// of visiting nodes more than once. This pays off when the goal is shallow
+/// but there are many nodes at each depth.
+pub fn iddfs<N, F, I>(start: N, goal: &N, mut neighbors: F, max_depth: u32) -> Option<u32>
+where
+    N: Eq,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    fn visit<N, F, I>(node: &N, goal: &N, neighbors: &mut F, depth: u32) -> bool
+    where
+        N: Eq,
+        F: FnMut(&N) -> I,
+        I: IntoIterator<Item = N>,
+    {
+        if node == goal {
+            return true;
+        }
+        depth > 0
+            && neighbors(node)
+                .into_iter()
+                .any(|next| visit(&next, goal, neighbors, depth - 1))
+    }
+
+    (0..=max_depth).find(|&depth| visit(&start, goal, &mut neighbors, depth))
+}
+
+/// Returned when a graph that was expected to be acyclic contains a cycle.
+/// Holds one of the nodes in the cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<N>(pub N);
+
+#[derive(Debug, Clone, Copy)]
+enum Visit {
+    New,
+    InProgress,
+    Done(u64),
+}
+
+/// Counts the distinct paths from `start` to `end` in a directed acyclic
+/// graph.
+///
+/// # Errors
+///
+/// Returns `Err(CycleError)` if a cycle is reachable from `start` (before
+/// reaching `end`), since there would be infinitely many paths.
+pub fn count_paths<N, C>(edges: &HashMap<N, C>, start: N, end: N) -> Result<u64, CycleError<N>>
+where
+    N: Clone + Eq + Hash,
+    C: AsRef<[N]>,
+{
+    fn visit<N, C>(
+        edges: &HashMap<N, C>,
+        node: &N,
+        end: &N,
+        visits: &mut HashMap<N, Visit>,
+    ) -> Result<u64, CycleError<N>>
+    where
+        N: Clone + Eq + Hash,
+        C: AsRef<[N]>,
+    {
+        if node == end {
+            return Ok(1);
+        }
+        match visits.get(node) {
+            Some(Visit::Done(count)) => return Ok(*count),
+            Some(Visit::InProgress) => return Err(CycleError(node.clone())),
+            Some(Visit::New) | None => {}
+        }
+        visits.insert(node.clone(), Visit::InProgress);
+        let mut count = 0;
+        if let Some(children) = edges.get(node) {
+            for child in children.as_ref() {
+                count += visit(edges, child, end, visits)?;
+            }
+        }
+        visits.insert(node.clone(), Visit::Done(count));
+        Ok(count)
+    }
+
+    visit(edges, &start, &end, &mut HashMap::new())
+}
+
+/// Same as [`count_paths`], but faster for nodes that can be used as small
+/// indices, since it doesn't need to hash them to keep track of visits.
+///
+/// # Errors
+///
+/// Returns `Err(CycleError)` if a cycle is reachable from `start` (before
+/// reaching `end`), since there would be infinitely many paths.
+pub fn count_paths_dense<N, C>(
+    edges: &HashMap<N, C>,
+    start: N,
+    end: N,
+) -> Result<u64, CycleError<N>>
+where
+    N: Copy + Eq + Hash + Into<usize>,
+    C: AsRef<[N]>,
+{
+    fn visit<N, C>(
+        edges: &HashMap<N, C>,
+        node: N,
+        end: N,
+        visits: &mut Vec<Visit>,
+    ) -> Result<u64, CycleError<N>>
+    where
+        N: Copy + Eq + Hash + Into<usize>,
+        C: AsRef<[N]>,
+    {
+        if node == end {
+            return Ok(1);
+        }
+        let index = node.into();
+        if index >= visits.len() {
+            visits.resize(index + 1, Visit::New);
+        }
+        match visits[index] {
+            Visit::Done(count) => return Ok(count),
+            Visit::InProgress => return Err(CycleError(node)),
+            Visit::New => {}
+        }
+        visits[index] = Visit::InProgress;
+        let mut count = 0;
+        if let Some(children) = edges.get(&node) {
+            for &child in children.as_ref() {
+                count += visit(edges, child, end, visits)?;
+            }
+        }
+        visits[index] = Visit::Done(count);
+        Ok(count)
+    }
+
+    visit(edges, start, end, &mut Vec::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: [&[u8]; 6] = [
+        b"..........",
+        b".######.#.",
+        b".#....#.#.",
+        b".#.##.#.#.",
+        b"...#....#.",
+        b"####.####.",
+    ];
+
+    fn neighbors(expanded: &mut usize, (x, y): (usize, usize)) -> Vec<((usize, usize), u64)> {
+        *expanded += 1;
+        [(0, -1), (1, 0), (0, 1), (-1, 0)]
+            .into_iter()
+            .filter_map(|(dx, dy)| {
+                let nx = x.checked_add_signed(dx)?;
+                let ny = y.checked_add_signed(dy)?;
+                (*MAZE.get(ny)?.get(nx)? == b'.').then_some(((nx, ny), 1))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn astar_same_cost_as_dijkstra() {
+        let start = (0, 0);
+        let goal = (4, 5);
+        let mut astar_expanded = 0;
+        let (astar_cost, path) = astar(
+            start,
+            &goal,
+            |&pos| neighbors(&mut astar_expanded, pos),
+            |&(x, y)| (x.abs_diff(goal.0) + y.abs_diff(goal.1)) as u64,
+        )
+        .unwrap();
+        let mut dijkstra_expanded = 0;
+        let (dijkstra_cost, _) =
+            dijkstra(start, &goal, |&pos| neighbors(&mut dijkstra_expanded, pos)).unwrap();
+        assert_eq!(astar_cost, 15);
+        assert_eq!(dijkstra_cost, 15);
+        assert_eq!(path.len(), 16);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        assert!(astar_expanded < dijkstra_expanded);
+    }
+
+    #[test]
+    fn astar_unreachable() {
+        let mut expanded = 0;
+        let result = astar((0, 0), &(3, 3), |&pos| neighbors(&mut expanded, pos), |_| 0);
+        assert_eq!(result, None);
+    }
+
+    /// The light patterns and buttons of the machines in the day 10 example,
+    /// where each button toggles some of the lights.
+    const MACHINES: [(u16, &[u16]); 3] = [
+        (0b0110, &[0b0001, 0b0101, 0b0010, 0b0011, 0b1010, 0b1100]),
+        (0b00010, &[0b10111, 0b00110, 0b10001, 0b11100, 0b01111]),
+        (0b011101, &[0b111110, 0b100110, 0b111011, 0b011000]),
+    ];
+
+    fn press_buttons(buttons: &[u16]) -> impl FnMut(&u16) -> Vec<u16> {
+        |&lights| buttons.iter().map(|&button| lights ^ button).collect()
+    }
+
+    #[test]
+    fn min_steps() {
+        let steps = MACHINES.map(|(goal, buttons)| min_steps_to(0, &goal, press_buttons(buttons)));
+        assert_eq!(steps, [Some(2), Some(3), Some(2)]);
+        let steps = MACHINES.map(|(goal, buttons)| {
+            min_steps_to_dense::<64, _, _, _>(0, &goal, press_buttons(buttons))
+        });
+        assert_eq!(steps, [Some(2), Some(3), Some(2)]);
+        assert_eq!(min_steps_to(0, &0, press_buttons(&[1])), Some(0));
+        // the buttons of the last machine can't toggle the first light alone
+        let (_, buttons) = MACHINES[2];
+        assert_eq!(min_steps_to(0, &0b100000, press_buttons(buttons)), None);
+    }
+
+    #[test]
+    fn iddfs_same_as_bfs() {
+        for (goal, buttons) in MACHINES {
+            let bfs = min_steps_to(0, &goal, press_buttons(buttons));
+            assert_eq!(iddfs(0, &goal, press_buttons(buttons), 10), bfs);
+        }
+        let (goal, buttons) = MACHINES[1];
+        assert_eq!(iddfs(0, &goal, press_buttons(buttons), 2), None);
+        let (_, buttons) = MACHINES[2];
+        assert_eq!(iddfs(0, &0b100000, press_buttons(buttons), 6), None);
+    }
+
+    #[test]
+    fn reachable() {
+        let (_, buttons) = MACHINES[0];
+        assert_eq!(reachable_count(0, press_buttons(buttons)), 16);
+        assert_eq!(
+            reachable_count_dense::<16, _, _, _>(0, press_buttons(buttons)),
+            16
+        );
+        // the first two buttons add up to the last one
+        let (_, buttons) = MACHINES[2];
+        assert_eq!(reachable_count(0, press_buttons(buttons)), 8);
+        assert_eq!(reachable_count(0, press_buttons(&[])), 1);
+    }
+
+    #[test]
+    #[should_panic = "out of bounds"]
+    fn dense_out_of_bounds() {
+        let (_, buttons) = MACHINES[0];
+        let _ = reachable_count_dense::<8, _, _, _>(0, press_buttons(buttons));
+    }
+
+    fn numbered_edges(edges: &[(u8, &[u8])]) -> HashMap<u8, Vec<u8>> {
+        edges
+            .iter()
+            .map(|&(node, children)| (node, children.to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn count_paths_dag() {
+        // 0 -> 1 -> 3 -> 4, 0 -> 2 -> 3, 1 -> 4
+        let edges = numbered_edges(&[(0, &[1, 2]), (1, &[3, 4]), (2, &[3]), (3, &[4])]);
+        assert_eq!(count_paths(&edges, 0, 4), Ok(3));
+        assert_eq!(count_paths(&edges, 2, 4), Ok(1));
+        assert_eq!(count_paths(&edges, 4, 0), Ok(0));
+        assert_eq!(count_paths(&edges, 3, 3), Ok(1));
+        assert_eq!(count_paths_dense(&edges, 0, 4), Ok(3));
+    }
+
+    #[test]
+    fn count_paths_cycle() {
+        // 1 -> 2 -> 3 -> 1
+        let edges = numbered_edges(&[(0, &[1]), (1, &[2]), (2, &[3, 4]), (3, &[1])]);
+        assert_eq!(count_paths(&edges, 0, 4), Err(CycleError(1)));
+        assert_eq!(count_paths_dense(&edges, 0, 4), Err(CycleError(1)));
+        // the cycle is not reachable from 2 without going through 4 first
+        let edges = numbered_edges(&[(2, &[4]), (4, &[5]), (5, &[4])]);
+        assert_eq!(count_paths(&edges, 2, 4), Ok(1));
+        assert_eq!(count_paths_dense(&edges, 2, 4), Ok(1));
+    }
+
+    #[test]
+    fn count_paths_labels() {
+        let edges = HashMap::from([
+            ("aaa", vec!["you", "hhh"]),
+            ("you", vec!["bbb", "ccc"]),
+            ("bbb", vec!["ddd", "eee"]),
+            ("ccc", vec!["ddd", "eee", "fff"]),
+            ("ddd", vec!["ggg"]),
+            ("eee", vec!["out"]),
+            ("fff", vec!["out"]),
+            ("ggg", vec!["out"]),
+            ("hhh", vec!["ccc", "fff", "iii"]),
+            ("iii", vec!["out"]),
+        ]);
+        assert_eq!(count_paths(&edges, "you", "out"), Ok(5));
+        assert_eq!(count_paths(&edges, "aaa", "out"), Ok(10));
+    }
+}
@@ -0,0 +1,1332 @@
+//! A generic rectangular grid, for puzzles that don't need a custom
+//! representation.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::{self, Display, Write as _},
+    hash::Hash,
+};
+
+use crate::{bits::BitGrid, graph::dijkstra, point::Direction4, utils::FromGridLike};
+
+const OFFSETS4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const OFFSETS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// Which cells count as adjacent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Connectivity {
+    /// Only orthogonal neighbors.
+    Four,
+    /// Orthogonal and diagonal neighbors.
+    Eight,
+}
+
+impl Connectivity {
+    const fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &OFFSETS4,
+            Connectivity::Eight => &OFFSETS8,
+        }
+    }
+}
+
+/// How distances between cells are measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Metric {
+    /// Only orthogonal steps, i.e. `|dx| + |dy|`.
+    Manhattan,
+    /// Diagonal steps are allowed, i.e. `max(|dx|, |dy|)`.
+    Chebyshev,
+}
+
+/// Returned when comparing grids of different sizes. Holds the `(width,
+/// height)` of both grids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DimensionMismatch(pub (usize, usize), pub (usize, usize));
+
+/// The outcome of [`Grid::simulate_splitters`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeamSimulation {
+    /// For each simulated row, how many splitters were hit by at least one
+    /// beam.
+    pub splits_per_row: Vec<usize>,
+    /// How many beams (counting each path separately, even when they
+    /// overlap) end up in each column after the last row.
+    ///
+    /// These are `u64` because the number of paths can double at every row:
+    /// the real day 7 input ends with 16716444407407 of them, which doesn't
+    /// fit in a `u32`.
+    pub beams: Vec<u64>,
+}
+
+/// A rectangular grid of cells, stored in row-major order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from its cells in row-major order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of cells is not `width * height`.
+    #[must_use]
+    pub fn new(cells: Vec<T>, width: usize, height: usize) -> Self {
+        assert_eq!(cells.len(), width * height, "cell count mismatch");
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// All the cells in row-major order.
+    #[must_use]
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Same as [`Grid::get`], but also accepts negative coordinates (which are
+    /// always out of bounds), so that neighbors can be probed by adding signed
+    /// offsets without checking for underflow first.
+    #[must_use]
+    pub fn get_signed(&self, x: isize, y: isize) -> Option<&T> {
+        self.get(x.try_into().ok()?, y.try_into().ok()?)
+    }
+
+    /// The cell at `(x, y)` as if the grid was repeated infinitely in every
+    /// direction, so that e.g. `(-1, -1)` is the bottom-right cell.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grid is empty.
+    #[must_use]
+    pub fn get_wrapping(&self, x: isize, y: isize) -> &T {
+        assert!(!self.cells.is_empty(), "grid is empty");
+        let width = isize::try_from(self.width).unwrap();
+        let height = isize::try_from(self.height).unwrap();
+        let x = x.rem_euclid(width).cast_unsigned();
+        let y = y.rem_euclid(height).cast_unsigned();
+        &self[(x, y)]
+    }
+
+    #[must_use]
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            self.cells.get_mut(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// The positions of the orthogonal neighbors of `(x, y)` whose cells
+    /// satisfy `predicate`.
+    pub fn neighbors4_matching(
+        &self,
+        x: usize,
+        y: usize,
+        predicate: impl Fn(&T) -> bool,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        self.adjacent(x, y, Connectivity::Four.offsets())
+            .filter(move |&position| predicate(&self[position]))
+    }
+
+    /// Same as [`Grid::neighbors4_matching`], but including the diagonal
+    /// neighbors.
+    pub fn neighbors8_matching(
+        &self,
+        x: usize,
+        y: usize,
+        predicate: impl Fn(&T) -> bool,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        self.adjacent(x, y, Connectivity::Eight.offsets())
+            .filter(move |&position| predicate(&self[position]))
+    }
+
+    /// Swaps rows `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either row is out of bounds.
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        assert!(a < self.height && b < self.height, "row out of bounds");
+        if a == b {
+            return;
+        }
+        let (first, second) = (a.min(b), a.max(b));
+        let (head, tail) = self.cells.split_at_mut(second * self.width);
+        head[first * self.width..(first + 1) * self.width].swap_with_slice(&mut tail[..self.width]);
+    }
+
+    /// Swaps columns `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either column is out of bounds.
+    pub fn swap_cols(&mut self, a: usize, b: usize) {
+        assert!(a < self.width && b < self.width, "column out of bounds");
+        for row in self.cells.chunks_mut(self.width) {
+            row.swap(a, b);
+        }
+    }
+
+    /// Sets every cell to `value`.
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        self.cells.fill(value);
+    }
+
+    /// The orthogonal neighbors of `(x, y)` that are in the grid, together
+    /// with the direction of the step from `(x, y)` to each of them.
+    pub fn neighbors4_dir(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> impl Iterator<Item = (Direction4, usize, usize, &T)> {
+        Direction4::ALL.into_iter().filter_map(move |direction| {
+            let (dx, dy) = direction.delta();
+            let nx = x.checked_add_signed(dx.into())?;
+            let ny = y.checked_add_signed(dy.into())?;
+            Some((direction, nx, ny, self.get(nx, ny)?))
+        })
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Displays the grid with `to_char` choosing the character for each cell,
+    /// one row per line.
+    pub fn display_with<F: Fn(&T) -> char>(&self, to_char: F) -> impl Display + use<'_, T, F> {
+        DisplayWith {
+            grid: self,
+            to_char,
+        }
+    }
+
+    /// Simulates beams going down from `start`, looking only at every
+    /// `step`-th row after it. When a beam reaches a splitter, it continues
+    /// from the cells to the left and to the right of it instead. Beams that
+    /// would leave the grid are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is 0.
+    #[must_use]
+    pub fn simulate_splitters(
+        &self,
+        start: (usize, usize),
+        step: usize,
+        is_splitter: impl Fn(&T) -> bool,
+    ) -> BeamSimulation {
+        assert!(step > 0, "step must be positive");
+        let mut beams = vec![0u64; self.width];
+        let mut next_beams = vec![0u64; self.width];
+        beams[start.0] = 1;
+        let mut splits_per_row = Vec::new();
+        for y in (start.1 + step..self.height).step_by(step) {
+            next_beams.fill(0);
+            let mut splits = 0;
+            for (x, &count) in beams.iter().enumerate() {
+                if count == 0 {
+                    continue;
+                }
+                if is_splitter(&self[(x, y)]) {
+                    splits += 1;
+                    if let Some(left) = x.checked_sub(1) {
+                        next_beams[left] += count;
+                    }
+                    if let Some(right) = next_beams.get_mut(x + 1) {
+                        *right += count;
+                    }
+                } else {
+                    next_beams[x] += count;
+                }
+            }
+            splits_per_row.push(splits);
+            std::mem::swap(&mut beams, &mut next_beams);
+        }
+        BeamSimulation {
+            splits_per_row,
+            beams,
+        }
+    }
+
+    /// Repeatedly replaces with `empty` all the cells (that aren't empty yet)
+    /// for which `removable` is true, until there are none left. Each round
+    /// looks at the grid as it was at the end of the previous round.
+    ///
+    /// Returns how many cells were removed in total.
+    pub fn erode_until_stable(
+        &mut self,
+        empty: T,
+        removable: impl Fn(&Self, usize, usize) -> bool,
+    ) -> usize
+    where
+        T: Clone + PartialEq,
+    {
+        let mut removed = 0;
+        loop {
+            let round = (0..self.height)
+                .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+                .filter(|&(x, y)| self[(x, y)] != empty && removable(self, x, y))
+                .collect::<Vec<_>>();
+            if round.is_empty() {
+                return removed;
+            }
+            removed += round.len();
+            for position in round {
+                self[position] = empty.clone();
+            }
+        }
+    }
+
+    /// Counts the paths from the top-left to the bottom-right corner that only
+    /// move right or down, and only go through `passable` cells.
+    #[must_use]
+    pub fn count_monotone_paths(&self, passable: impl Fn(&T) -> bool) -> u64 {
+        if self.cells.is_empty() {
+            return 0;
+        }
+        // paths[x] holds the number of paths reaching column x of the
+        // current row
+        let mut paths = vec![0u64; self.width];
+        paths[0] = 1;
+        for row in self.rows() {
+            let mut from_left = 0;
+            for (count, cell) in paths.iter_mut().zip(row) {
+                *count = if passable(cell) {
+                    *count + from_left
+                } else {
+                    0
+                };
+                from_left = *count;
+            }
+        }
+        paths[self.width - 1]
+    }
+
+    /// Computes the distance of every cell to the nearest cell satisfying
+    /// `is_feature`, with a breadth-first search from all the features at
+    /// once. Cells are set to `u32::MAX` if there are no features at all.
+    #[must_use]
+    pub fn distance_transform(&self, is_feature: impl Fn(&T) -> bool, metric: Metric) -> Grid<u32> {
+        let offsets = match metric {
+            Metric::Manhattan => Connectivity::Four.offsets(),
+            Metric::Chebyshev => Connectivity::Eight.offsets(),
+        };
+        let mut distances = Grid::new(vec![u32::MAX; self.cells.len()], self.width, self.height);
+        let mut queue = VecDeque::new();
+        for (index, cell) in self.cells.iter().enumerate() {
+            if is_feature(cell) {
+                distances.cells[index] = 0;
+                queue.push_back((index % self.width, index / self.width));
+            }
+        }
+        while let Some((x, y)) = queue.pop_front() {
+            let next = distances[(x, y)] + 1;
+            for (nx, ny) in self.adjacent(x, y, offsets) {
+                if distances[(nx, ny)] == u32::MAX {
+                    distances[(nx, ny)] = next;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        distances
+    }
+
+    /// Returns the positions of all the cells reachable from `start` through
+    /// cells that satisfy `predicate`, including `start` itself (unless it
+    /// doesn't satisfy `predicate`, in which case the result is empty).
+    ///
+    /// Uses an explicit stack, so it's safe to use on arbitrarily large
+    /// regions.
+    #[must_use]
+    pub fn flood_fill(
+        &self,
+        start: (usize, usize),
+        predicate: impl Fn(&T) -> bool,
+        connectivity: Connectivity,
+    ) -> Vec<(usize, usize)> {
+        if !self.get(start.0, start.1).is_some_and(&predicate) {
+            return Vec::new();
+        }
+        let mut visited = vec![false; self.cells.len()];
+        self.fill_unvisited(start, &predicate, connectivity, &mut visited)
+    }
+
+    /// Returns all the maximal regions of connected cells that satisfy
+    /// `predicate`. Regions are ordered by their first cell in row-major
+    /// order, and each region starts with that cell.
+    #[must_use]
+    pub fn connected_components(
+        &self,
+        predicate: impl Fn(&T) -> bool,
+        connectivity: Connectivity,
+    ) -> Vec<Vec<(usize, usize)>> {
+        let mut visited = vec![false; self.cells.len()];
+        let mut components = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !visited[y * self.width + x] && predicate(&self[(x, y)]) {
+                    components.push(self.fill_unvisited(
+                        (x, y),
+                        &predicate,
+                        connectivity,
+                        &mut visited,
+                    ));
+                }
+            }
+        }
+        components
+    }
+
+    /// Flood fill from `start`, which must satisfy `predicate`, skipping and
+    /// then marking cells in `visited`.
+    fn fill_unvisited(
+        &self,
+        start: (usize, usize),
+        predicate: impl Fn(&T) -> bool,
+        connectivity: Connectivity,
+        visited: &mut [bool],
+    ) -> Vec<(usize, usize)> {
+        let mut region = Vec::new();
+        visited[start.1 * self.width + start.0] = true;
+        let mut stack = vec![start];
+        while let Some((x, y)) = stack.pop() {
+            region.push((x, y));
+            for (nx, ny) in self.adjacent(x, y, connectivity.offsets()) {
+                let index = ny * self.width + nx;
+                if !visited[index] && predicate(&self.cells[index]) {
+                    visited[index] = true;
+                    stack.push((nx, ny));
+                }
+            }
+        }
+        region
+    }
+
+    /// Returns the number of cells that [`Grid::flood_fill`] would return,
+    /// without collecting their positions. Visited cells are tracked with a
+    /// bitset, so this is cheaper on large grids.
+    #[must_use]
+    pub fn flood_fill_count(
+        &self,
+        start: (usize, usize),
+        predicate: impl Fn(&T) -> bool,
+        connectivity: Connectivity,
+    ) -> usize {
+        if !self.get(start.0, start.1).is_some_and(&predicate) {
+            return 0;
+        }
+        let mut visited = vec![0u64; self.cells.len().div_ceil(64)];
+        let mut visit = |index: usize| {
+            let (word, bit) = (index / 64, index % 64);
+            let new = visited[word] >> bit & 1 == 0;
+            visited[word] |= 1 << bit;
+            new
+        };
+        visit(start.1 * self.width + start.0);
+        let mut count = 0;
+        let mut stack = vec![start];
+        while let Some((x, y)) = stack.pop() {
+            count += 1;
+            for (nx, ny) in self.adjacent(x, y, connectivity.offsets()) {
+                let index = ny * self.width + nx;
+                if predicate(&self.cells[index]) && visit(index) {
+                    stack.push((nx, ny));
+                }
+            }
+        }
+        count
+    }
+
+    /// Renders the grid with each region of connected cells satisfying
+    /// `predicate` drawn with its own letter, cycling through `a` to `z`, and
+    /// every other cell drawn as `.`. Handy for checking regions by eye.
+    #[must_use]
+    pub fn render_regions(
+        &self,
+        predicate: impl Fn(&T) -> bool,
+        connectivity: Connectivity,
+    ) -> String {
+        let mut labels = vec![b'.'; self.cells.len()];
+        let components = self.connected_components(predicate, connectivity);
+        for (region, component) in components.iter().enumerate() {
+            #[allow(clippy::cast_possible_truncation)]
+            let label = b'a' + (region % 26) as u8;
+            for &(x, y) in component {
+                labels[y * self.width + x] = label;
+            }
+        }
+        let mut rendered = String::with_capacity(labels.len() + self.height);
+        for row in labels.chunks(self.width.max(1)) {
+            rendered.extend(row.iter().map(|&label| char::from(label)));
+            rendered.push('\n');
+        }
+        rendered
+    }
+
+    /// Returns the top-left positions of all the places where `pattern`
+    /// appears in the grid, in row-major order. Occurrences can overlap.
+    #[must_use]
+    pub fn find_subgrid(&self, pattern: &Grid<T>) -> Vec<(usize, usize)>
+    where
+        T: PartialEq,
+    {
+        let mut found = Vec::new();
+        if pattern.cells.is_empty() || pattern.width > self.width || pattern.height > self.height {
+            return found;
+        }
+        for y in 0..=self.height - pattern.height {
+            for x in 0..=self.width - pattern.width {
+                let matches = pattern.rows().enumerate().all(|(dy, pattern_row)| {
+                    let start = (y + dy) * self.width + x;
+                    self.cells[start..start + pattern.width] == *pattern_row
+                });
+                if matches {
+                    found.push((x, y));
+                }
+            }
+        }
+        found
+    }
+
+    /// Counts the occurrences of each cell value.
+    #[must_use]
+    pub fn histogram(&self) -> HashMap<T, usize>
+    where
+        T: Hash + Eq + Clone,
+    {
+        let mut counts = HashMap::new();
+        for cell in &self.cells {
+            *counts.entry(cell.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Returns the positions of the cells that differ between the two grids,
+    /// in row-major order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(DimensionMismatch)` if the grids don't have the same
+    /// width and height.
+    pub fn diff(&self, other: &Grid<T>) -> Result<Vec<(usize, usize)>, DimensionMismatch>
+    where
+        T: PartialEq,
+    {
+        if (self.width, self.height) != (other.width, other.height) {
+            return Err(DimensionMismatch(
+                (self.width, self.height),
+                (other.width, other.height),
+            ));
+        }
+        Ok(self
+            .cells
+            .iter()
+            .zip(&other.cells)
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(index, _)| (index % self.width, index / self.width))
+            .collect())
+    }
+
+    /// Finds the cheapest path from `start` to `goal`, where `neighbors`
+    /// yields the positions reachable from a position together with the cost
+    /// of each move. The moves don't need to be between adjacent cells, so
+    /// this also works for mazes with portals.
+    ///
+    /// Returns the total cost and the path, including both `start` and `goal`.
+    /// See [`dijkstra`].
+    pub fn dijkstra_custom<I>(
+        &self,
+        start: (usize, usize),
+        goal: (usize, usize),
+        mut neighbors: impl FnMut(&Self, (usize, usize)) -> I,
+    ) -> Option<(u64, Vec<(usize, usize)>)>
+    where
+        I: IntoIterator<Item = ((usize, usize), u64)>,
+    {
+        dijkstra(start, &goal, |&position| neighbors(self, position))
+    }
+
+    /// The in-bounds positions at the given offsets from `(x, y)`.
+    fn adjacent(
+        &self,
+        x: usize,
+        y: usize,
+        offsets: &[(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> {
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let nx = x.checked_add_signed(dx)?;
+            let ny = y.checked_add_signed(dy)?;
+            (nx < self.width && ny < self.height).then_some((nx, ny))
+        })
+    }
+
+    /// Returns a copy of the grid rotated 90° clockwise, with width and
+    /// height swapped.
+    #[must_use]
+    pub fn rotate_cw(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.transformed(self.height, self.width, |x, y| (y, self.height - 1 - x))
+    }
+
+    /// Returns a copy of the grid rotated 90° counterclockwise, with width and
+    /// height swapped.
+    #[must_use]
+    pub fn rotate_ccw(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.transformed(self.height, self.width, |x, y| (self.width - 1 - y, x))
+    }
+
+    /// Returns a copy of the grid rotated 180°.
+    #[must_use]
+    pub fn rotate_180(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.transformed(self.width, self.height, |x, y| {
+            (self.width - 1 - x, self.height - 1 - y)
+        })
+    }
+
+    /// Returns a copy of the grid mirrored left to right.
+    #[must_use]
+    pub fn flip_horizontal(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.transformed(self.width, self.height, |x, y| (self.width - 1 - x, y))
+    }
+
+    /// Returns a copy of the grid mirrored top to bottom.
+    #[must_use]
+    pub fn flip_vertical(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.transformed(self.width, self.height, |x, y| (x, self.height - 1 - y))
+    }
+
+    /// Builds a `width` x `height` grid where each cell is copied from the
+    /// position of this grid returned by `source`.
+    fn transformed(
+        &self,
+        width: usize,
+        height: usize,
+        source: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| self[source(x, y)].clone())
+            .collect();
+        Grid::new(cells, width, height)
+    }
+}
+
+struct DisplayWith<'a, T, F> {
+    grid: &'a Grid<T>,
+    to_char: F,
+}
+
+impl<T, F: Fn(&T) -> char> Display for DisplayWith<'_, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.grid.rows().enumerate() {
+            if y > 0 {
+                f.write_char('\n')?;
+            }
+            for cell in row {
+                f.write_char((self.to_char)(cell))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes the cells one row per line, with no separators.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                f.write_char('\n')?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Grid<bool> {
+    /// Packs the grid into a [`BitGrid`], which takes one bit per cell.
+    #[must_use]
+    pub fn as_bitset(&self) -> BitGrid {
+        let mut bits = BitGrid::new(self.width, self.height);
+        for (y, row) in self.rows().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                bits.set(x, y, cell);
+            }
+        }
+        bits
+    }
+}
+
+impl<T> std::ops::Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) out of bounds"
+        );
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> std::ops::IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) out of bounds"
+        );
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl<T> FromGridLike for Grid<T> {
+    type Cell = T;
+    fn from_cells(cells: Vec<T>, width: usize, height: usize) -> Self {
+        Self::new(cells, width, height)
+    }
+}
+
+/// A copy of a [`Grid`] surrounded by a one cell wide border of a sentinel
+/// value, so that every cell of the original grid has all eight neighbors
+/// and stencils can read them without bounds checks.
+///
+/// Coordinates are shifted by one compared to the original grid: its cell
+/// `(x, y)` is at `(x + 1, y + 1)` here, and the border is at `x == 0`,
+/// `y == 0`, `x == width() + 1` and `y == height() + 1`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BorderedGrid<T> {
+    cells: Vec<T>,
+    stride: usize,
+    width: usize,
+    height: usize,
+}
+
+impl<T: Clone> BorderedGrid<T> {
+    #[must_use]
+    pub fn new(grid: &Grid<T>, sentinel: T) -> Self {
+        let stride = grid.width + 2;
+        let mut cells = Vec::with_capacity(stride * (grid.height + 2));
+        cells.extend(std::iter::repeat_n(sentinel.clone(), stride));
+        // not `grid.rows()`, which yields nothing for a zero-width grid
+        for y in 0..grid.height {
+            cells.push(sentinel.clone());
+            cells.extend_from_slice(&grid.cells[y * grid.width..][..grid.width]);
+            cells.push(sentinel.clone());
+        }
+        cells.extend(std::iter::repeat_n(sentinel, stride));
+        Self {
+            cells,
+            stride,
+            width: grid.width,
+            height: grid.height,
+        }
+    }
+}
+
+impl<T> BorderedGrid<T> {
+    /// The width of the original grid, without the border.
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the original grid, without the border.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The cell at `(x, y)`, in shifted coordinates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `(x, y)` is past the border. To keep this fast, an `x` past
+    /// the border is only detected in debug builds (in release builds it
+    /// wraps around to the next row).
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> &T {
+        debug_assert!(x < self.stride, "({x}, {y}) out of bounds");
+        &self.cells[y * self.stride + x]
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> &mut T {
+        debug_assert!(x < self.stride, "({x}, {y}) out of bounds");
+        &mut self.cells[y * self.stride + x]
+    }
+
+    /// The positions of the cells of the original grid, in shifted
+    /// coordinates and row-major order.
+    pub fn interior(&self) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        let width = self.width;
+        (1..=self.height).flat_map(move |y| (1..=width).map(move |x| (x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::AsciiUtils;
+
+    fn grid(s: &[u8]) -> Grid<u8> {
+        s.grid_like().unwrap().into_grid()
+    }
+
+    #[test]
+    fn get() {
+        let grid = grid(b"abc\ndef");
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get(0, 0), Some(&b'a'));
+        assert_eq!(grid.get(2, 1), Some(&b'f'));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+        assert_eq!(grid[(1, 1)], b'e');
+    }
+
+    #[test]
+    fn get_signed() {
+        let grid = grid(b"abc\ndef");
+        assert_eq!(grid.get_signed(0, 0), Some(&b'a'));
+        assert_eq!(grid.get_signed(2, 1), Some(&b'f'));
+        assert_eq!(grid.get_signed(1, 1), Some(&b'e'));
+        assert_eq!(grid.get_signed(-1, 0), None);
+        assert_eq!(grid.get_signed(0, -1), None);
+        assert_eq!(grid.get_signed(3, 0), None);
+        assert_eq!(grid.get_signed(0, 2), None);
+        assert_eq!(grid.get_signed(isize::MIN, isize::MAX), None);
+    }
+
+    #[test]
+    fn get_wrapping() {
+        let grid = grid(b"abc\ndef");
+        assert_eq!(grid.get_wrapping(0, 0), &b'a');
+        assert_eq!(grid.get_wrapping(-1, -1), &b'f');
+        assert_eq!(grid.get_wrapping(-3, -2), &b'a');
+        assert_eq!(grid.get_wrapping(4, 3), &b'e');
+        assert_eq!(grid.get_wrapping(3_000_002, -2_000_001), &b'f');
+        assert_eq!(grid.get_wrapping(isize::MAX, isize::MIN), &b'b');
+    }
+
+    #[test]
+    fn count_monotone_paths() {
+        let open = grid(b"...\n...\n...");
+        assert_eq!(open.count_monotone_paths(|&c| c == b'.'), 6);
+        let obstacle = grid(b"...\n.#.\n...");
+        assert_eq!(obstacle.count_monotone_paths(|&c| c == b'.'), 2);
+        let blocked = grid(b"..#\n.#.\n#..");
+        assert_eq!(blocked.count_monotone_paths(|&c| c == b'.'), 0);
+    }
+
+    #[test]
+    fn neighbors_matching() {
+        let grid = grid(b"@@.\n.@@\n@..");
+        let occupied = |&cell: &u8| cell == b'@';
+        assert_eq!(grid.neighbors8_matching(1, 1, occupied).count(), 4);
+        assert_eq!(grid.neighbors4_matching(1, 1, occupied).count(), 2);
+        assert_eq!(
+            grid.neighbors8_matching(0, 0, occupied).collect::<Vec<_>>(),
+            [(1, 0), (1, 1)]
+        );
+        assert_eq!(grid.neighbors4_matching(0, 2, occupied).count(), 0);
+        assert_eq!(grid.neighbors8_matching(2, 2, |_| true).count(), 3);
+    }
+
+    #[test]
+    fn simulate_splitters() {
+        let manifold = grid(
+            b".......S.......\n\
+              ...............\n\
+              .......^.......\n\
+              ...............\n\
+              ......^.^......\n\
+              ...............\n\
+              .....^.^.^.....\n\
+              ...............\n\
+              ....^.^...^....\n\
+              ...............\n\
+              ...^.^...^.^...\n\
+              ...............\n\
+              ..^...^.....^..\n\
+              ...............\n\
+              .^.^.^.^.^...^.\n\
+              ...............",
+        );
+        let simulation = manifold.simulate_splitters((7, 0), 2, |&cell| cell == b'^');
+        assert_eq!(simulation.splits_per_row, [1, 2, 3, 3, 4, 3, 5]);
+        assert_eq!(simulation.splits_per_row.iter().sum::<usize>(), 21);
+        assert_eq!(simulation.beams.iter().sum::<u64>(), 40);
+        // the empty rows don't change anything
+        let every_row = manifold.simulate_splitters((7, 0), 1, |&cell| cell == b'^');
+        assert_eq!(every_row.beams, simulation.beams);
+        // beams split off the edges are lost
+        let edges = grid(b"S.\n^.");
+        assert_eq!(
+            edges
+                .simulate_splitters((0, 0), 1, |&cell| cell == b'^')
+                .beams,
+            [0, 1]
+        );
+    }
+
+    #[test]
+    fn bordered_grid() {
+        let plain = grid(b"abc\ndef");
+        let bordered = BorderedGrid::new(&plain, b'#');
+        assert_eq!((bordered.width(), bordered.height()), (3, 2));
+        for (x, y) in bordered.interior() {
+            assert_eq!(bordered.get(x, y), &plain[(x - 1, y - 1)]);
+        }
+        assert_eq!(bordered.interior().count(), 6);
+        for x in 0..5 {
+            assert_eq!(bordered.get(x, 0), &b'#');
+            assert_eq!(bordered.get(x, 3), &b'#');
+        }
+        for y in 0..4 {
+            assert_eq!(bordered.get(0, y), &b'#');
+            assert_eq!(bordered.get(4, y), &b'#');
+        }
+        // all the neighbors of a corner cell can be read
+        let neighbors = [
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (0, 1),
+            (2, 1),
+            (0, 2),
+            (1, 2),
+            (2, 2),
+        ]
+        .map(|(x, y)| *bordered.get(x, y));
+        assert_eq!(&neighbors, b"####b#de");
+        let mut bordered = bordered;
+        *bordered.get_mut(2, 2) = b'E';
+        assert_eq!(bordered.get(2, 2), &b'E');
+    }
+
+    #[test]
+    fn bordered_grid_zero_width() {
+        let plain = Grid::new(Vec::<u8>::new(), 0, 2);
+        let bordered = BorderedGrid::new(&plain, b'#');
+        assert_eq!((bordered.width(), bordered.height()), (0, 2));
+        assert_eq!(bordered.interior().count(), 0);
+        for y in 0..4 {
+            assert_eq!(bordered.get(0, y), &b'#');
+            assert_eq!(bordered.get(1, y), &b'#');
+        }
+    }
+
+    #[test]
+    fn simulate_splitters_many_paths() {
+        // every beam hits a splitter at every row, so the number of paths
+        // doubles each time
+        let rows: usize = 40;
+        let (width, height) = (2 * rows + 3, rows + 1);
+        let start = rows + 1;
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                if y > 0 && (x + y + start + 1).is_multiple_of(2) {
+                    b'^'
+                } else {
+                    b'.'
+                }
+            })
+            .collect();
+        let galton = Grid::new(cells, width, height);
+        let simulation = galton.simulate_splitters((start, 0), 1, |&cell| cell == b'^');
+        assert_eq!(simulation.splits_per_row, (1..=rows).collect::<Vec<_>>());
+        let total = simulation.beams.iter().sum::<u64>();
+        assert!(total > u64::from(u32::MAX));
+        assert_eq!(total, 1u64 << rows);
+        // the counts follow Pascal's triangle
+        assert_eq!(simulation.beams[start], 137_846_528_820);
+    }
+
+    #[test]
+    fn erode_until_stable() {
+        let mut rolls = grid(
+            b"..@@.@@@@.\n\
+              @@@.@.@.@@\n\
+              @@@@@.@.@@\n\
+              @.@@@@..@.\n\
+              @@.@@@@.@@\n\
+              .@@@@@@@.@\n\
+              .@.@.@.@@@\n\
+              @.@@@.@@@@\n\
+              .@@@@@@@@.\n\
+              @.@.@@@.@.",
+        );
+        let accessible = |grid: &Grid<u8>, x, y| {
+            grid.neighbors8_matching(x, y, |&cell| cell == b'@').count() < 4
+        };
+        assert_eq!(rolls.erode_until_stable(b'.', accessible), 43);
+        assert_eq!(rolls.histogram()[&b'@'], 71 - 43);
+        // already stable
+        assert_eq!(rolls.erode_until_stable(b'.', accessible), 0);
+    }
+
+    #[test]
+    fn neighbors4_dir() {
+        let grid = grid(b"abc\ndef\nghi");
+        for (x, y) in [(1, 1), (0, 0), (2, 1)] {
+            for (direction, nx, ny, &cell) in grid.neighbors4_dir(x, y) {
+                let (dx, dy) = direction.delta();
+                assert_eq!(nx as isize - x as isize, isize::from(dx));
+                assert_eq!(ny as isize - y as isize, isize::from(dy));
+                assert_eq!(cell, grid[(nx, ny)]);
+            }
+        }
+        let around_center = grid
+            .neighbors4_dir(1, 1)
+            .map(|(direction, _, _, &cell)| (direction, cell))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            around_center,
+            [
+                (Direction4::Up, b'b'),
+                (Direction4::Right, b'f'),
+                (Direction4::Down, b'h'),
+                (Direction4::Left, b'd'),
+            ]
+        );
+        let from_corner = grid.neighbors4_dir(0, 0).map(|(direction, ..)| direction);
+        assert_eq!(
+            from_corner.collect::<Vec<_>>(),
+            [Direction4::Right, Direction4::Down]
+        );
+    }
+
+    crate::grid_cell_enum! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        enum Cell {
+            Empty => b'.',
+            Roll => b'@',
+        }
+    }
+
+    #[test]
+    fn display() {
+        let input = "..@@.\n@@@.@\n.@...";
+        let grid: Grid<Cell> = input.as_bytes().grid_like().unwrap().into_grid();
+        assert_eq!(grid.to_string(), input);
+        assert_eq!(
+            grid.display_with(|cell| cell.byte() as char).to_string(),
+            input
+        );
+        let marked = grid.display_with(|&cell| if cell == Cell::Roll { 'x' } else { ' ' });
+        assert_eq!(marked.to_string(), "  xx \nxxx x\n x   ");
+        assert_eq!(Grid::<u8>::new(vec![], 0, 0).to_string(), "");
+    }
+
+    #[test]
+    fn swap_rows_and_cols() {
+        let original = grid(b"abc\ndef\nghi");
+        let mut swapped = original.clone();
+        swapped.swap_rows(0, 2);
+        assert_eq!(swapped, grid(b"ghi\ndef\nabc"));
+        swapped.swap_rows(2, 0);
+        assert_eq!(swapped, original);
+        swapped.swap_cols(1, 2);
+        assert_eq!(swapped, grid(b"acb\ndfe\ngih"));
+        swapped.swap_cols(1, 2);
+        assert_eq!(swapped, original);
+        swapped.swap_rows(1, 1);
+        swapped.swap_cols(0, 0);
+        assert_eq!(swapped, original);
+    }
+
+    #[test]
+    #[should_panic = "row out of bounds"]
+    fn swap_rows_out_of_bounds() {
+        grid(b"ab\ncd").swap_rows(0, 2);
+    }
+
+    #[test]
+    fn fill() {
+        let mut grid = grid(b"abc\ndef");
+        grid.fill(b'x');
+        assert!(grid.cells().iter().all(|&c| c == b'x'));
+        assert_eq!(grid.cells().len(), 6);
+    }
+
+    #[test]
+    fn distance_transform() {
+        let grid = grid(b".....\n.....\n..#..\n.....\n.....");
+        let chebyshev = grid.distance_transform(|&c| c == b'#', Metric::Chebyshev);
+        assert_eq!(chebyshev[(2, 2)], 0);
+        assert_eq!(chebyshev[(1, 2)], 1);
+        assert_eq!(chebyshev[(1, 1)], 1);
+        for corner in [(0, 0), (4, 0), (0, 4), (4, 4)] {
+            assert_eq!(chebyshev[corner], 2);
+        }
+        let manhattan = grid.distance_transform(|&c| c == b'#', Metric::Manhattan);
+        assert_eq!(manhattan[(1, 1)], 2);
+        assert_eq!(manhattan[(0, 0)], 4);
+        assert_eq!(manhattan[(4, 3)], 3);
+    }
+
+    #[test]
+    fn histogram() {
+        let grid = grid(
+            b"..@@.@@@@.\n\
+              @@@.@.@.@@\n\
+              @@@@@.@.@@\n\
+              @.@@@@..@.\n\
+              @@.@@@@.@@\n\
+              .@@@@@@@.@\n\
+              .@.@.@.@@@\n\
+              @.@@@.@@@@\n\
+              .@@@@@@@@.\n\
+              @.@.@@@.@.",
+        );
+        let histogram = grid.histogram();
+        assert_eq!(histogram.len(), 2);
+        assert_eq!(histogram[&b'@'], 71);
+        assert_eq!(histogram[&b'.'], 29);
+    }
+
+    #[test]
+    fn diff() {
+        let before = grid(b"..@@\n@@@.\n.@..");
+        let after = grid(b"..@.\n@@@.\n.@.@");
+        assert_eq!(before.diff(&after), Ok(vec![(3, 0), (3, 2)]));
+        assert_eq!(after.diff(&before), Ok(vec![(3, 0), (3, 2)]));
+        assert_eq!(before.diff(&before), Ok(vec![]));
+        assert_eq!(
+            before.diff(&grid(b"..@@\n@@@.")),
+            Err(DimensionMismatch((4, 3), (4, 2)))
+        );
+    }
+
+    #[test]
+    fn find_subgrid() {
+        let haystack = grid(b"ab.a\ncdac\n..cd\nab.a");
+        let pattern = grid(b"ab\ncd");
+        assert_eq!(haystack.find_subgrid(&pattern), [(0, 0)]);
+        let pattern = grid(b"a\nc");
+        assert_eq!(haystack.find_subgrid(&pattern), [(0, 0), (3, 0), (2, 1)]);
+        let pattern = grid(b".a\nac");
+        assert_eq!(haystack.find_subgrid(&pattern), [(2, 0)]);
+        let too_big = grid(b"ab.a.\ncdac.");
+        assert!(haystack.find_subgrid(&too_big).is_empty());
+    }
+
+    #[test]
+    fn flood_fill() {
+        let grid = grid(b"##..#\n#...#\n..#..\n.#.##");
+        let mut region = grid.flood_fill((0, 0), |&c| c == b'#', Connectivity::Four);
+        region.sort_unstable();
+        assert_eq!(region, [(0, 0), (0, 1), (1, 0)]);
+        let mut region = grid.flood_fill((2, 2), |&c| c == b'#', Connectivity::Eight);
+        region.sort_unstable();
+        assert_eq!(region, [(1, 3), (2, 2), (3, 3), (4, 3)]);
+        let region = grid.flood_fill((2, 2), |&c| c == b'#', Connectivity::Four);
+        assert_eq!(region, [(2, 2)]);
+        let region = grid.flood_fill((2, 0), |&c| c == b'.', Connectivity::Four);
+        assert_eq!(region.len(), 10);
+        assert!(
+            grid.flood_fill((0, 0), |&c| c == b'.', Connectivity::Four)
+                .is_empty()
+        );
+        assert!(
+            grid.flood_fill((9, 9), |&c| c == b'#', Connectivity::Four)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn as_bitset() {
+        let cells = b"#..#.##.".iter().map(|&c| c == b'#').collect();
+        let grid = Grid::new(cells, 4, 2);
+        let bits = grid.as_bitset();
+        assert_eq!(bits.count_ones(), 4);
+        assert_eq!(bits.row(0), [0b1001]);
+        assert_eq!(bits.row(1), [0b0110]);
+    }
+
+    #[test]
+    fn connected_components() {
+        let grid = grid(b"##..#\n#...#\n..#..\n.#.##");
+        let mut components = grid.connected_components(|&c| c == b'#', Connectivity::Four);
+        assert_eq!(components.len(), 5);
+        for component in &mut components[..] {
+            component.sort_unstable_by_key(|&(x, y)| (y, x));
+        }
+        assert_eq!(
+            components,
+            [
+                vec![(0, 0), (1, 0), (0, 1)],
+                vec![(4, 0), (4, 1)],
+                vec![(2, 2)],
+                vec![(1, 3)],
+                vec![(3, 3), (4, 3)],
+            ]
+        );
+        let components = grid.connected_components(|&c| c == b'#', Connectivity::Eight);
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[2].len(), 4);
+        assert_eq!(components[2][0], (2, 2));
+        assert!(
+            grid.connected_components(|&c| c == b'x', Connectivity::Four)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn render_regions() {
+        let grid = grid(b"##..#\n#...#\n..#..\n.#.##");
+        let rendered = grid.render_regions(|&c| c == b'#', Connectivity::Four);
+        assert_eq!(rendered, "aa..b\na...b\n..c..\n.d.ee\n");
+        let rendered = grid.render_regions(|&c| c == b'#', Connectivity::Eight);
+        assert_eq!(rendered, "aa..b\na...b\n..c..\n.c.cc\n");
+    }
+
+    #[test]
+    fn flood_fill_count() {
+        let grid = grid(b"##..#\n#...#\n..#..\n.#.##");
+        for (start, connectivity) in [
+            ((0, 0), Connectivity::Four),
+            ((2, 2), Connectivity::Eight),
+            ((2, 2), Connectivity::Four),
+            ((4, 0), Connectivity::Eight),
+        ] {
+            let count = grid.flood_fill_count(start, |&c| c == b'#', connectivity);
+            let region = grid.flood_fill(start, |&c| c == b'#', connectivity);
+            assert_eq!(count, region.len());
+        }
+        assert_eq!(
+            grid.flood_fill_count((2, 0), |&c| c == b'.', Connectivity::Four),
+            10
+        );
+        assert_eq!(
+            grid.flood_fill_count((0, 0), |&c| c == b'.', Connectivity::Four),
+            0
+        );
+        // spans several words of the bitset
+        let big = Grid::new(vec![true; 300 * 7], 300, 7);
+        assert_eq!(
+            big.flood_fill_count((150, 3), |&c| c, Connectivity::Four),
+            2100
+        );
+    }
+
+    #[test]
+    fn rotations() {
+        let original = grid(b"abc\ndef");
+        let cw = original.rotate_cw();
+        assert_eq!((cw.width(), cw.height()), (2, 3));
+        assert_eq!(cw, grid(b"da\neb\nfc"));
+        assert_eq!(original.rotate_ccw(), grid(b"cf\nbe\nad"));
+        assert_eq!(original.rotate_180(), grid(b"fed\ncba"));
+        assert_eq!(cw.rotate_cw(), original.rotate_180());
+        assert_eq!(cw.rotate_ccw(), original);
+        assert_eq!(cw.rotate_cw().rotate_cw().rotate_cw(), original);
+    }
+
+    #[test]
+    fn flips() {
+        let original = grid(b"abc\ndef");
+        assert_eq!(original.flip_horizontal(), grid(b"cba\nfed"));
+        assert_eq!(original.flip_vertical(), grid(b"def\nabc"));
+        assert_eq!(
+            original.flip_horizontal().flip_vertical(),
+            original.rotate_180()
+        );
+        assert_eq!(original.flip_vertical().flip_vertical(), original);
+    }
+
+    #[test]
+    fn dijkstra_custom() {
+        let grid = grid(b"S.#..\n..#.E\n.....");
+        let portal = ((0, 0), (3, 0));
+        let walk = |grid: &Grid<u8>, (x, y): (usize, usize)| {
+            grid.adjacent(x, y, Connectivity::Four.offsets())
+                .filter(|&position| grid[position] != b'#')
+                .map(|position| (position, 1))
+                .collect::<Vec<_>>()
+        };
+        let (cost, path) = grid.dijkstra_custom((0, 0), (4, 1), walk).unwrap();
+        assert_eq!(cost, 7);
+        assert_eq!(path.len(), 8);
+        let with_portal = |grid: &Grid<u8>, position| {
+            let mut edges = walk(grid, position);
+            if position == portal.0 {
+                edges.push((portal.1, 1));
+            }
+            edges
+        };
+        let (cost, path) = grid.dijkstra_custom((0, 0), (4, 1), with_portal).unwrap();
+        assert_eq!(cost, 3);
+        // there are two cheapest paths after the portal, through (4, 0) or
+        // (3, 1), so only check what they have in common
+        assert_eq!(path.len(), 4);
+        assert_eq!(path[..2], [(0, 0), (3, 0)]);
+        assert_eq!(path.last(), Some(&(4, 1)));
+        // an expensive portal is not worth taking
+        let slow_portal = |grid: &Grid<u8>, position| {
+            let mut edges = walk(grid, position);
+            if position == portal.0 {
+                edges.push((portal.1, 10));
+            }
+            edges
+        };
+        let (cost, _) = grid.dijkstra_custom((0, 0), (4, 1), slow_portal).unwrap();
+        assert_eq!(cost, 7);
+    }
+}
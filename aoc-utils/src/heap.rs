@@ -0,0 +1,154 @@
+//! Priority queues.
+
+/// Returned when pushing into a full [`ArrayHeap`]. Holds the item that
+/// didn't fit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeapFull<T>(pub T);
+
+/// A max-heap like `std::collections::BinaryHeap`, but storing at most `N`
+/// items inline instead of allocating.
+pub struct ArrayHeap<const N: usize, T> {
+    items: [Option<T>; N],
+    len: usize,
+}
+
+impl<const N: usize, T: Ord> ArrayHeap<N, T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            items: std::array::from_fn(|_| None),
+            len: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The greatest item, if any.
+    #[must_use]
+    pub fn peek(&self) -> Option<&T> {
+        self.items.first()?.as_ref()
+    }
+
+    /// Pushes an item onto the heap.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(HeapFull)` with the item if the heap already holds `N`
+    /// items.
+    pub fn push(&mut self, item: T) -> Result<(), HeapFull<T>> {
+        if self.len == N {
+            return Err(HeapFull(item));
+        }
+        let mut index = self.len;
+        self.items[index] = Some(item);
+        self.len += 1;
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if self.items[index] <= self.items[parent] {
+                break;
+            }
+            self.items.swap(index, parent);
+            index = parent;
+        }
+        Ok(())
+    }
+
+    /// Removes the greatest item and returns it, or `None` if the heap is
+    /// empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        self.items.swap(0, self.len);
+        let top = self.items[self.len].take();
+        let mut index = 0;
+        loop {
+            let left = 2 * index + 1;
+            let right = left + 1;
+            let mut largest = index;
+            if left < self.len && self.items[left] > self.items[largest] {
+                largest = left;
+            }
+            if right < self.len && self.items[right] > self.items[largest] {
+                largest = right;
+            }
+            if largest == index {
+                break;
+            }
+            self.items.swap(index, largest);
+            index = largest;
+        }
+        top
+    }
+}
+
+impl<const N: usize, T: Ord> Default for ArrayHeap<N, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BinaryHeap;
+
+    use super::*;
+
+    fn xorshift(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    #[test]
+    fn same_order_as_binary_heap() {
+        let mut state = 0x2025;
+        for _ in 0..20 {
+            let mut heap = ArrayHeap::<64, u64>::new();
+            let mut expected = BinaryHeap::new();
+            let count = xorshift(&mut state) % 65;
+            for _ in 0..count {
+                // a small range so that there are duplicates
+                let item = xorshift(&mut state) % 32;
+                heap.push(item).unwrap();
+                expected.push(item);
+                // interleave some pops with the pushes
+                if item.is_multiple_of(5) {
+                    assert_eq!(heap.pop(), expected.pop());
+                }
+            }
+            assert_eq!(heap.len(), expected.len());
+            assert_eq!(heap.peek(), expected.peek());
+            while let Some(item) = expected.pop() {
+                assert_eq!(heap.pop(), Some(item));
+            }
+            assert_eq!(heap.pop(), None);
+        }
+    }
+
+    #[test]
+    fn full() {
+        let mut heap = ArrayHeap::<2, _>::new();
+        assert_eq!(heap.push(1), Ok(()));
+        assert_eq!(heap.push(3), Ok(()));
+        assert_eq!(heap.push(2), Err(HeapFull(2)));
+        assert_eq!(heap.pop(), Some(3));
+        assert_eq!(heap.push(2), Ok(()));
+        assert_eq!(heap.len(), heap.capacity());
+    }
+}
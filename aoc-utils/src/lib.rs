@@ -5,10 +5,27 @@
 //! solutions, I decided it's okay to collect my own utilities over time and use
 //! them in my solutions.
 
+pub mod bits;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod heap;
+pub mod linalg;
+pub mod point;
 pub mod range;
+pub mod shape;
 pub mod testing;
 pub mod utils;
+#[cfg(feature = "viz")]
+pub mod viz;
 
+pub use bits::*;
+pub use geometry::*;
+pub use graph::*;
+pub use grid::*;
+pub use heap::*;
+pub use linalg::*;
+pub use point::*;
 pub use range::*;
 pub use testing::*;
 pub use utils::*;
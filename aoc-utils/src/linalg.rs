@@ -0,0 +1,156 @@
+//! Linear algebra helpers.
+
+/// Finds the minimum number of buttons to press to turn all-off lights into
+/// `target`, where each button toggles the lights set in its bit pattern.
+/// Only the lowest `width` bits of `buttons` and `target` are considered.
+///
+/// This is a linear system over GF(2) (pressing a button twice is the same as
+/// not pressing it at all), solved with Gaussian elimination. Returns `None`
+/// if `target` can't be reached.
+///
+/// Finding the minimum weight solution requires trying every assignment of
+/// the free variables, so the cost is exponential in their number (but not in
+/// the number of buttons or lights).
+///
+/// # Panics
+///
+/// Panics if there are more than 64 buttons, if `width` is larger than 64, or
+/// if all 64 variables are free (only possible with 64 buttons that toggle
+/// none of the lights).
+#[must_use]
+pub fn solve_gf2(buttons: &[u64], target: u64, width: usize) -> Option<u32> {
+    assert!(buttons.len() <= 64, "too many buttons");
+    assert!(width <= 64, "too many lights");
+    // one equation for each light: which buttons toggle it, and whether it
+    // has to end up on
+    let mut rows = (0..width)
+        .map(|light| {
+            let mask = buttons
+                .iter()
+                .enumerate()
+                .filter(|&(_, button)| button >> light & 1 == 1)
+                .fold(0u64, |mask, (index, _)| mask | 1 << index);
+            (mask, target >> light & 1 == 1)
+        })
+        .collect::<Vec<_>>();
+
+    // reduced row echelon form
+    let mut pivots = Vec::new();
+    for col in 0..buttons.len() {
+        let rank = pivots.len();
+        let Some(found) = (rank..rows.len()).find(|&row| rows[row].0 >> col & 1 == 1) else {
+            continue;
+        };
+        rows.swap(rank, found);
+        let (pivot_mask, pivot_rhs) = rows[rank];
+        for (row, (mask, rhs)) in rows.iter_mut().enumerate() {
+            if row != rank && *mask >> col & 1 == 1 {
+                *mask ^= pivot_mask;
+                *rhs ^= pivot_rhs;
+            }
+        }
+        pivots.push(col);
+    }
+    // rows past the rank have no variables left, so they must be 0 = 0
+    if rows[pivots.len()..].iter().any(|&(_, rhs)| rhs) {
+        return None;
+    }
+
+    let pivot_mask = pivots.iter().fold(0u64, |mask, col| mask | 1 << col);
+    let free = (0..buttons.len())
+        .filter(|col| pivot_mask >> col & 1 == 0)
+        .collect::<Vec<_>>();
+    let assignments = 1u64
+        .checked_shl(u32::try_from(free.len()).unwrap())
+        .expect("too many free variables");
+    (0..assignments)
+        .map(|assignment| {
+            let mut solution = free
+                .iter()
+                .enumerate()
+                .filter(|&(bit, _)| assignment >> bit & 1 == 1)
+                .fold(0u64, |solution, (_, col)| solution | 1 << col);
+            for (row, &col) in pivots.iter().enumerate() {
+                let (mask, rhs) = rows[row];
+                let toggled = (mask & solution).count_ones() % 2 == 1;
+                if toggled != rhs {
+                    solution |= 1 << col;
+                }
+            }
+            solution.count_ones()
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(buttons: &[u64], target: u64) -> Option<u32> {
+        solve_gf2(buttons, target, 16)
+    }
+
+    #[test]
+    fn solve_gf2_unique() {
+        // x0 = 1, x0 ^ x1 = 0
+        assert_eq!(solve(&[0b11, 0b10], 0b01), Some(2));
+        assert_eq!(solve(&[0b11, 0b10], 0b00), Some(0));
+        assert_eq!(solve(&[0b11, 0b10], 0b10), Some(1));
+    }
+
+    #[test]
+    fn solve_gf2_free_variables() {
+        // the third button is the same as pressing the other two
+        assert_eq!(solve(&[0b001, 0b110, 0b111], 0b111), Some(1));
+        assert_eq!(solve(&[0b001, 0b110, 0b111], 0b110), Some(1));
+        assert_eq!(solve(&[0b011, 0b011], 0b011), Some(1));
+    }
+
+    #[test]
+    fn solve_gf2_impossible() {
+        assert_eq!(solve(&[0b011, 0b110], 0b001), None);
+        assert_eq!(solve(&[], 0b1), None);
+    }
+
+    #[test]
+    fn solve_gf2_day10_example_patterns() {
+        // [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1)
+        let first = solve(&[0b0001, 0b0101, 0b0010, 0b0011, 0b1010, 0b1100], 0b0110);
+        // [...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4)
+        let second = solve(&[0b10111, 0b00110, 0b10001, 0b11100, 0b01111], 0b00010);
+        // [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2)
+        let third = solve(&[0b111110, 0b100110, 0b111011, 0b011000], 0b011101);
+        assert_eq!(first, Some(2));
+        assert_eq!(second, Some(3));
+        assert_eq!(third, Some(2));
+    }
+
+    #[test]
+    fn solve_gf2_day10_example() {
+        let machines: [(&[u64], u64, usize); 3] = [
+            (&[0b1000, 0b1010, 0b0100, 0b1100, 0b0101, 0b0011], 0b0110, 4),
+            (&[0b11101, 0b01100, 0b10001, 0b00111, 0b11110], 0b01000, 5),
+            (&[0b011111, 0b011001, 0b110111, 0b000110], 0b101110, 6),
+        ];
+        let total = machines
+            .into_iter()
+            .map(|(buttons, target, width)| solve_gf2(buttons, target, width).unwrap())
+            .sum::<u32>();
+        assert_eq!(total, 7);
+    }
+
+    #[test]
+    fn solve_gf2_wide() {
+        // lights beyond the first 16, each toggled by its own button
+        let buttons = (0..40).map(|light| 1 << light).collect::<Vec<u64>>();
+        let target = 1 << 39 | 1 << 20 | 1;
+        assert_eq!(solve_gf2(&buttons, target, 40), Some(3));
+        assert_eq!(solve_gf2(&buttons[..39], target, 40), None);
+    }
+
+    #[test]
+    #[should_panic = "too many free variables"]
+    fn solve_gf2_all_free() {
+        let _ = solve_gf2(&[0; 64], 0, 1);
+    }
+}
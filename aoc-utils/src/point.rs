@@ -0,0 +1,363 @@
+//! Points in the plane and in space, and directions for walking around a grid.
+//!
+//! Coordinates follow the usual convention for grids: `x` grows to the right
+//! and `y` grows downwards, so [`Direction4::Up`] decreases `y`.
+
+use std::ops::{Add, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    #[must_use]
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    #[must_use]
+    pub fn into_array(self) -> [T; 2] {
+        [self.x, self.y]
+    }
+
+    #[must_use]
+    pub fn into_tuple(self) -> (T, T) {
+        (self.x, self.y)
+    }
+}
+
+impl<T> From<[T; 2]> for Point2<T> {
+    fn from([x, y]: [T; 2]) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    #[must_use]
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    #[must_use]
+    pub fn into_array(self) -> [T; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    #[must_use]
+    pub fn into_tuple(self) -> (T, T, T) {
+        (self.x, self.y, self.z)
+    }
+}
+
+impl<T> From<[T; 3]> for Point3<T> {
+    fn from([x, y, z]: [T; 3]) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+macro_rules! point3_impl {
+    ($($t:ty => $u:ty),+) => {
+        $(
+            impl Point3<$t> {
+                /// The square of the Euclidean distance, which is enough to
+                /// compare distances without taking square roots.
+                #[must_use]
+                pub fn squared_distance(self, other: Self) -> $u {
+                    let dx = self.x.abs_diff(other.x);
+                    let dy = self.y.abs_diff(other.y);
+                    let dz = self.z.abs_diff(other.z);
+                    dx * dx + dy * dy + dz * dz
+                }
+
+                /// Whether the Euclidean distance from `other` is at most
+                /// `distance`.
+                #[must_use]
+                pub fn within_distance(self, other: Self, distance: $u) -> bool {
+                    distance
+                        .checked_mul(distance)
+                        .is_none_or(|max| self.squared_distance(other) <= max)
+                }
+            }
+        )+
+    };
+}
+
+point3_impl!(i32 => u32, i64 => u64, isize => usize, u32 => u32, u64 => u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction4 {
+    /// All the directions, clockwise starting from `Up`.
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    /// The `(dx, dy)` offset of a single step in this direction.
+    #[must_use]
+    pub const fn delta(self) -> (i8, i8) {
+        match self {
+            Self::Up => (0, -1),
+            Self::Right => (1, 0),
+            Self::Down => (0, 1),
+            Self::Left => (-1, 0),
+        }
+    }
+
+    #[must_use]
+    pub const fn turn_right(self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+
+    #[must_use]
+    pub const fn turn_left(self) -> Self {
+        self.turn_right().turn_right().turn_right()
+    }
+
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        self.turn_right().turn_right()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    /// All the directions, clockwise starting from `Up`.
+    pub const ALL: [Self; 8] = [
+        Self::Up,
+        Self::UpRight,
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+    ];
+
+    /// The `(dx, dy)` offset of a single step in this direction.
+    #[must_use]
+    pub const fn delta(self) -> (i8, i8) {
+        match self {
+            Self::Up => (0, -1),
+            Self::UpRight => (1, -1),
+            Self::Right => (1, 0),
+            Self::DownRight => (1, 1),
+            Self::Down => (0, 1),
+            Self::DownLeft => (-1, 1),
+            Self::Left => (-1, 0),
+            Self::UpLeft => (-1, -1),
+        }
+    }
+}
+
+impl From<Direction4> for Direction8 {
+    fn from(direction: Direction4) -> Self {
+        match direction {
+            Direction4::Up => Self::Up,
+            Direction4::Right => Self::Right,
+            Direction4::Down => Self::Down,
+            Direction4::Left => Self::Left,
+        }
+    }
+}
+
+macro_rules! point2_impl {
+    ($($t:ty),+) => {
+        $(
+            impl Point2<$t> {
+                /// Moves `n` steps in the given direction.
+                #[must_use]
+                pub fn step(self, direction: impl Into<Direction8>, n: $t) -> Self {
+                    let (dx, dy) = direction.into().delta();
+                    Self::new(self.x + <$t>::from(dx) * n, self.y + <$t>::from(dy) * n)
+                }
+
+                /// All the points within Manhattan distance `radius` of this
+                /// one, including itself, row by row.
+                ///
+                /// Points can have negative coordinates, so for positions in
+                /// a grid they still need to be checked against its bounds.
+                ///
+                /// # Panics
+                ///
+                /// Panics if `radius` doesn't fit in the coordinate type.
+                pub fn within_manhattan(self, radius: u32) -> impl Iterator<Item = Self> {
+                    let r = <$t>::try_from(radius).expect("radius should fit the coordinates");
+                    (-r..=r).flat_map(move |dy| {
+                        let span = r - dy.abs();
+                        (-span..=span).map(move |dx| Self::new(self.x + dx, self.y + dy))
+                    })
+                }
+            }
+
+            impl Add<Direction4> for Point2<$t> {
+                type Output = Self;
+                fn add(self, rhs: Direction4) -> Self {
+                    self.step(rhs, 1)
+                }
+            }
+
+            impl Add<Direction8> for Point2<$t> {
+                type Output = Self;
+                fn add(self, rhs: Direction8) -> Self {
+                    self.step(rhs, 1)
+                }
+            }
+        )+
+    };
+}
+
+point2_impl!(isize, i32, i64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_direction() {
+        assert_eq!(
+            Point2::<isize>::new(1, 1) + Direction4::Right,
+            Point2::new(2, 1)
+        );
+        assert_eq!(Point2::new(1, 1) + Direction4::Up, Point2::new(1, 0));
+        assert_eq!(Point2::new(1, 1) + Direction8::DownLeft, Point2::new(0, 2));
+        assert_eq!(Point2::new(3, 4) - Point2::new(1, 1), Point2::new(2, 3));
+    }
+
+    #[test]
+    fn conversions() {
+        let p = Point2::from([3u32, 4]);
+        assert_eq!(p, Point2::new(3, 4));
+        assert_eq!(p.into_array(), [3, 4]);
+        assert_eq!(Point2::from(p.into_tuple()), p);
+        let [x, y] = Point2::from((-1i64, 2)).into_array();
+        assert_eq!((x, y), (-1, 2));
+        let q = Point3::from((1u32, 2, 3));
+        assert_eq!(q.into_array(), [1, 2, 3]);
+        assert_eq!(Point3::from(q.into_array()).into_tuple(), (1, 2, 3));
+    }
+
+    #[test]
+    fn step() {
+        let origin = Point2::<isize>::new(0, 0);
+        assert_eq!(origin.step(Direction4::Left, 3), Point2::new(-3, 0));
+        assert_eq!(origin.step(Direction8::UpRight, 2), Point2::new(2, -2));
+        assert_eq!(origin.step(Direction4::Down, 0), origin);
+    }
+
+    #[test]
+    fn within_manhattan() {
+        let center = Point2::<i32>::new(0, 0);
+        for r in 0..5u32 {
+            let points = center.within_manhattan(r).collect::<Vec<_>>();
+            assert_eq!(points.len(), (2 * r * (r + 1) + 1) as usize);
+            let r = i32::try_from(r).unwrap();
+            assert!(points.iter().all(|p| p.x.abs() + p.y.abs() <= r));
+        }
+        // radius 1 is the center and its four neighbors
+        let mut points = Point2::<i64>::new(3, 0)
+            .within_manhattan(1)
+            .collect::<Vec<_>>();
+        points.sort();
+        assert_eq!(
+            points,
+            [(2, 0), (3, -1), (3, 0), (3, 1), (4, 0)].map(Point2::from)
+        );
+    }
+
+    #[test]
+    fn within_distance() {
+        let origin = Point3::<i64>::new(0, 0, 0);
+        let p = Point3::new(2, -3, 6);
+        assert_eq!(origin.squared_distance(p), 49);
+        assert!(origin.within_distance(p, 7));
+        assert!(!origin.within_distance(p, 6));
+        // sqrt(1 + 1 + 1) is between 1 and 2
+        let q = Point3::<u32>::new(1, 1, 1);
+        assert!(!Point3::<u32>::new(0, 0, 0).within_distance(q, 1));
+        assert!(Point3::<u32>::new(2, 2, 2).within_distance(q, 2));
+    }
+
+    #[test]
+    fn turns() {
+        assert_eq!(Direction4::Up.turn_right(), Direction4::Right);
+        assert_eq!(Direction4::Up.turn_left(), Direction4::Left);
+        assert_eq!(Direction4::Left.opposite(), Direction4::Right);
+        // walking around a square gets back to the start
+        let mut position = Point2::<i64>::new(5, 5);
+        let mut direction = Direction4::Up;
+        for _ in 0..4 {
+            position = position.step(direction, 2);
+            direction = direction.turn_right();
+        }
+        assert_eq!(position, Point2::new(5, 5));
+    }
+}
@@ -1,5 +1,7 @@
 //! Utilities for working with ranges and intervals.
 
+use crate::utils::NumberExt;
+
 pub trait Overlaps {
     fn overlaps(&self, other: &Self) -> bool;
 }
@@ -126,9 +128,9 @@ macro_rules! interval_impl {
             fn try_from(value: std::ops::RangeInclusive<$t>) -> Result<Self, Self::Error> {
                 Ok(Self {
                     start: *value.start(),
-                    length: (*value.end() - *value.start())
-                        .checked_add(1)
-                        .ok_or(TryFromRangeError)?,
+                    length: len_inclusive(&value)
+                        .try_into()
+                        .map_err(|_| TryFromRangeError)?,
                 })
             }
         }
@@ -138,6 +140,309 @@ macro_rules! interval_impl {
 interval_impl!(u32);
 interval_impl!(u64);
 
+/// A set of integers stored as sorted, disjoint and non-adjacent inclusive
+/// ranges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeSet<T> {
+    ranges: Vec<std::ops::RangeInclusive<T>>,
+}
+
+impl<T> RangeSet<T> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// The merged ranges, sorted by their start.
+    #[must_use]
+    pub fn ranges(&self) -> &[std::ops::RangeInclusive<T>] {
+        &self.ranges
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+impl<T> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> RangeSet<T>
+where
+    T: NumberExt + Copy + Ord + std::ops::Sub<Output = T>,
+{
+    /// Builds a set from possibly overlapping or adjacent ranges. Empty ranges
+    /// are ignored.
+    pub fn from_ranges(ranges: impl IntoIterator<Item = std::ops::RangeInclusive<T>>) -> Self {
+        Self::from_unsorted(ranges.into_iter().collect())
+    }
+
+    /// Same as [`RangeSet::from_ranges`], but sorts the given vector in place
+    /// instead of collecting the ranges into a new one.
+    ///
+    /// Once built, [`RangeSet::contains`] takes O(log n) instead of checking
+    /// every range.
+    #[must_use]
+    pub fn from_unsorted(mut ranges: Vec<std::ops::RangeInclusive<T>>) -> Self {
+        ranges.retain(|range| !range.is_empty());
+        ranges.sort_unstable_by_key(|range| *range.start());
+        let mut merged: Vec<std::ops::RangeInclusive<T>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            if let Some(last) = merged.last_mut() {
+                // the start is greater than the end of the last range here, so
+                // subtracting one can't underflow, while adding one to the end
+                // could overflow
+                if *range.start() <= *last.end() || *range.start() - T::one() == *last.end() {
+                    if range.end() > last.end() {
+                        *last = *last.start()..=*range.end();
+                    }
+                    continue;
+                }
+            }
+            merged.push(range);
+        }
+        Self { ranges: merged }
+    }
+
+    /// Iterates over the ranges, each with the number of integers in the set
+    /// that come before it, i.e. the rank of its start among all the values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of a range doesn't fit in a `usize`.
+    pub fn ranges_with_offsets(
+        &self,
+    ) -> impl Iterator<Item = (std::ops::RangeInclusive<T>, usize)> + '_
+    where
+        std::ops::RangeInclusive<T>: HasExtent<Extent = u128>,
+    {
+        self.ranges.iter().scan(0usize, |offset, range| {
+            let len: usize = len_inclusive(range).try_into().expect("range too long");
+            let current = *offset;
+            *offset += len;
+            Some((range.clone(), current))
+        })
+    }
+
+    /// The number of integers in the set. This is a `u128` so that it can
+    /// count every `u64`.
+    #[must_use]
+    pub fn len(&self) -> u128
+    where
+        std::ops::RangeInclusive<T>: HasExtent<Extent = u128>,
+    {
+        self.ranges.iter().map(len_inclusive).sum()
+    }
+
+    #[must_use]
+    pub fn contains(&self, n: T) -> bool {
+        let index = self.ranges.partition_point(|range| *range.end() < n);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.contains(&n))
+    }
+}
+
+impl<T> FromIterator<std::ops::RangeInclusive<T>> for RangeSet<T>
+where
+    T: NumberExt + Copy + Ord + std::ops::Sub<Output = T>,
+{
+    fn from_iter<I: IntoIterator<Item = std::ops::RangeInclusive<T>>>(iter: I) -> Self {
+        Self::from_ranges(iter)
+    }
+}
+
+/// The number of integers covered both by some range in `a` and by some
+/// range in `b`. Ranges within each slice may overlap.
+#[must_use]
+pub fn intersection_length<T>(
+    a: &[std::ops::RangeInclusive<T>],
+    b: &[std::ops::RangeInclusive<T>],
+) -> u128
+where
+    T: NumberExt + Copy + Ord + std::ops::Sub<Output = T>,
+    std::ops::RangeInclusive<T>: HasExtent<Extent = u128>,
+{
+    let a = RangeSet::from_ranges(a.iter().cloned());
+    let b = RangeSet::from_ranges(b.iter().cloned());
+    let (mut a, mut b) = (a.ranges(), b.ranges());
+    let mut total = 0;
+    while let ([first_a, rest_a @ ..], [first_b, rest_b @ ..]) = (a, b) {
+        let start = *first_a.start().max(first_b.start());
+        let end = *first_a.end().min(first_b.end());
+        total += len_inclusive(&(start..=end));
+        // the range that ends first can't overlap anything else
+        if first_a.end() < first_b.end() {
+            a = rest_a;
+        } else {
+            b = rest_b;
+        }
+    }
+    total
+}
+
+/// Whether a [`SweepEvent`] is where a range starts or ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Boundary {
+    Start,
+    End,
+}
+
+/// A range boundary produced by [`sweep`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SweepEvent<T> {
+    pub position: T,
+    pub boundary: Boundary,
+    /// How many ranges cover the integers right after this event: from
+    /// `position` onwards for a start, from `position + 1` onwards for an end
+    /// (since ranges are inclusive, `position` itself is still covered by
+    /// the range that ends there).
+    pub count: usize,
+}
+
+/// Returns the starts and ends of all the (non-empty) ranges, sorted by
+/// position, together with how many ranges overlap after each of them. When
+/// a range starts where another ends, the start comes first.
+///
+/// Useful to find which integers are covered by at least `k` ranges, which
+/// can't be answered after merging them with [`RangeSet`].
+pub fn sweep<T: Copy + Ord>(
+    ranges: &[std::ops::RangeInclusive<T>],
+) -> impl Iterator<Item = SweepEvent<T>> {
+    let mut events = ranges
+        .iter()
+        .filter(|range| !range.is_empty())
+        .flat_map(|range| {
+            [
+                (*range.start(), Boundary::Start),
+                (*range.end(), Boundary::End),
+            ]
+        })
+        .collect::<Vec<_>>();
+    events.sort_unstable();
+    events
+        .into_iter()
+        .scan(0usize, |count, (position, boundary)| {
+            match boundary {
+                Boundary::Start => *count += 1,
+                Boundary::End => *count -= 1,
+            }
+            Some(SweepEvent {
+                position,
+                boundary,
+                count: *count,
+            })
+        })
+}
+
+/// The number of integers in `range`, which can be one more than the largest
+/// value of `T`, e.g. for `0..=u64::MAX`. Empty ranges have length 0.
+#[must_use]
+pub fn len_inclusive<T>(range: &std::ops::RangeInclusive<T>) -> u128
+where
+    std::ops::RangeInclusive<T>: HasExtent<Extent = u128>,
+{
+    range.extent()
+}
+
+/// Integers that can tell whether they have a successor. Used by the range
+/// helpers that need to step past the end of a range without overflowing.
+pub trait Successor: Sized {
+    /// `self + 1`, or `None` if `self` is the maximum value.
+    fn successor(self) -> Option<Self>;
+}
+
+/// Returns the smallest integer `>= start` that is not covered by any of the
+/// intervals.
+///
+/// # Panics
+///
+/// Panics if every integer from `start` up to the maximum value of `T` is
+/// covered.
+#[must_use]
+pub fn first_gap<T>(intervals: &[std::ops::RangeInclusive<T>], start: T) -> T
+where
+    T: NumberExt + Successor + Copy + Ord + std::ops::Sub<Output = T>,
+{
+    let set = RangeSet::from_ranges(intervals.iter().cloned());
+    let mut candidate = start;
+    for range in set.ranges() {
+        if *range.start() > candidate {
+            break;
+        }
+        if *range.end() >= candidate {
+            candidate = range.end().successor().expect("no gap up to MAX");
+        }
+    }
+    candidate
+}
+
+/// Splits `range` into consecutive subranges, starting a new one at each of
+/// `points`, which must be sorted. Points outside of the range, or equal to
+/// its start, don't cause a split.
+#[must_use]
+pub fn split_at<T>(
+    range: &std::ops::RangeInclusive<T>,
+    points: &[T],
+) -> Vec<std::ops::RangeInclusive<T>>
+where
+    T: NumberExt + Copy + Ord + std::ops::Sub<Output = T>,
+{
+    if range.is_empty() {
+        return Vec::new();
+    }
+    let mut parts = Vec::with_capacity(points.len() + 1);
+    let mut start = *range.start();
+    for &point in points {
+        if point > start && point <= *range.end() {
+            parts.push(start..=point - T::one());
+            start = point;
+        }
+    }
+    parts.push(start..=*range.end());
+    parts
+}
+
+macro_rules! range_set_impl {
+    ($($t:ty),+) => {
+        $(
+            impl RangeSet<$t> {
+                /// Iterates over every integer in the set, in ascending order.
+                pub fn values(&self) -> impl Iterator<Item = $t> + '_ {
+                    // RangeInclusive iteration stops at the end without
+                    // overflowing, even if the end is MAX
+                    self.ranges.iter().flat_map(Clone::clone)
+                }
+            }
+
+            impl HasExtent for std::ops::RangeInclusive<$t> {
+                type Extent = u128;
+                fn extent(&self) -> u128 {
+                    if self.is_empty() {
+                        0
+                    } else {
+                        // abs_diff is unsigned, so this can't overflow even
+                        // for signed types
+                        self.end().abs_diff(*self.start()) as u128 + 1
+                    }
+                }
+            }
+
+            impl Successor for $t {
+                fn successor(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+            }
+        )+
+    };
+}
+
+range_set_impl!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
 #[cfg(test)]
 mod interval_tests {
     macro_rules! test_interval_impl {
@@ -153,6 +458,20 @@ mod interval_tests {
                     assert_eq!(Interval::new(0x8000_0000, 0xF000_0000).end(), 0x1_7000_0000);
                 }
 
+                #[test]
+                fn try_from_inclusive() {
+                    assert_eq!(Interval::try_from(3..=5).unwrap(), Interval::new(3, 3));
+                    assert_eq!(
+                        Interval::try_from(std::ops::RangeInclusive::new(5, 4)).unwrap(),
+                        Interval::new(5, 0)
+                    );
+                    assert_eq!(
+                        Interval::try_from(1..=$t::MAX).unwrap(),
+                        Interval::new(1, $t::MAX)
+                    );
+                    assert!(Interval::try_from(0..=$t::MAX).is_err());
+                }
+
                 #[test]
                 fn intersection() {
                     assert_eq!(
@@ -242,3 +561,233 @@ mod interval_tests {
     test_interval_impl!(u32);
     test_interval_impl!(u64);
 }
+
+#[cfg(test)]
+mod range_set_tests {
+    use super::RangeSet;
+
+    #[test]
+    fn merge() {
+        let set = RangeSet::from_ranges([10u64..=14, 3..=5, 16..=20, 12..=18, 6..=6]);
+        assert_eq!(set.ranges(), &[3..=6, 10..=20]);
+        let empty = RangeSet::<u64>::from_ranges([]);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn merge_signed_far_apart() {
+        // the distance between the ranges doesn't fit in the type, so the
+        // adjacency check must not compute it
+        let set = RangeSet::from_ranges([-5i32..=-5, i32::MAX..=i32::MAX]);
+        assert_eq!(set.ranges(), &[-5..=-5, i32::MAX..=i32::MAX]);
+        let set = RangeSet::from_ranges([i64::MIN..=i64::MIN, i64::MAX..=i64::MAX]);
+        assert_eq!(set.ranges(), &[i64::MIN..=i64::MIN, i64::MAX..=i64::MAX]);
+        let set = RangeSet::from_ranges([i64::MIN..=-1, 0..=0]);
+        assert_eq!(set.ranges(), &[i64::MIN..=0]);
+    }
+
+    #[test]
+    fn contains() {
+        let set = RangeSet::from_ranges([3u64..=5, 10..=14]);
+        assert!(!set.contains(2));
+        assert!(set.contains(3));
+        assert!(set.contains(5));
+        assert!(!set.contains(6));
+        assert!(set.contains(12));
+        assert!(!set.contains(15));
+    }
+
+    #[test]
+    fn ranges_with_offsets() {
+        let set = RangeSet::from_ranges([10u64..=14, 20..=22]);
+        assert_eq!(
+            set.ranges_with_offsets().collect::<Vec<_>>(),
+            [(10..=14, 0), (20..=22, 5)]
+        );
+        // the 7th value (0-based) is in the second range
+        let (range, offset) = set
+            .ranges_with_offsets()
+            .take_while(|&(_, offset)| offset <= 7)
+            .last()
+            .unwrap();
+        assert_eq!(range.start() + (7 - offset) as u64, 22);
+        assert_eq!(set.values().nth(7), Some(22));
+    }
+
+    #[test]
+    fn contains_matches_naive() {
+        let mut state = 0x2025u64;
+        let mut random = |max: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % max
+        };
+        for _ in 0..20 {
+            let ranges = (0..random(20))
+                .map(|_| {
+                    let start = random(200);
+                    start..=start + random(20)
+                })
+                .collect::<Vec<_>>();
+            let set = RangeSet::from_unsorted(ranges.clone());
+            for n in 0..250 {
+                let naive = ranges.iter().any(|range| range.contains(&n));
+                assert_eq!(set.contains(n), naive, "{n} in {ranges:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn split_at() {
+        use super::split_at;
+        assert_eq!(split_at(&(1..=10), &[4, 7]), [1..=3, 4..=6, 7..=10]);
+        assert_eq!(split_at(&(1..=10), &[]), [1..=10]);
+        assert_eq!(split_at(&(1..=10), &[0, 1, 10, 11]), [1..=9, 10..=10]);
+        assert_eq!(split_at(&(1..=10), &[5, 5]), [1..=4, 5..=10]);
+        assert!(split_at(&std::ops::RangeInclusive::new(5, 1), &[3]).is_empty());
+    }
+
+    #[test]
+    fn first_gap() {
+        use super::first_gap;
+        assert_eq!(first_gap(&[0..=4, 5..=9], 0), 10);
+        assert_eq!(first_gap(&[1..=3], 0), 0);
+        assert_eq!(first_gap(&[1..=3], 2), 4);
+        assert_eq!(first_gap(&[1..=3, 10..=20, 5..=9], 2), 4);
+        assert_eq!(first_gap(&[1..=3, 4..=20, 22..=30], 2), 21);
+        assert_eq!(first_gap(&[], 7), 7);
+    }
+
+    #[test]
+    fn signed() {
+        use super::{first_gap, split_at};
+        let set = RangeSet::from_ranges([-5i32..=-3, 4..=6, -2..=0, 7..=7, i32::MIN..=-10]);
+        assert_eq!(set.ranges(), &[i32::MIN..=-10, -5..=0, 4..=7]);
+        assert!(set.contains(-1));
+        assert!(!set.contains(-7));
+        assert!(!set.contains(2));
+        // adjacent across zero, and at the extremes
+        let set = RangeSet::from_ranges([1i32..=i32::MAX, i32::MIN..=0]);
+        assert_eq!(set.ranges(), &[i32::MIN..=i32::MAX]);
+        assert_eq!(first_gap(&[-5i32..=-3, -2..=0, 2..=3], -4), 1);
+        assert_eq!(first_gap(&[-5i32..=-3], -10), -10);
+        assert_eq!(split_at(&(-3i32..=3), &[-1, 2]), [-3..=-2, -1..=1, 2..=3]);
+        assert_eq!(first_gap(&[0usize..=4, 6..=8], 3), 5);
+    }
+
+    #[test]
+    fn intersection_length() {
+        use super::intersection_length;
+        // 3..=5 and 10..=11
+        assert_eq!(intersection_length(&[1u64..=5, 10..=12], &[3..=11]), 5);
+        assert_eq!(intersection_length(&[3u64..=11], &[1..=5, 10..=12]), 5);
+        assert_eq!(intersection_length(&[1u64..=5], &[6..=10]), 0);
+        assert_eq!(intersection_length(&[1u64..=5], &[]), 0);
+        // overlaps within the same side are only counted once
+        assert_eq!(intersection_length(&[1u64..=5, 2..=6], &[0..=10]), 6);
+        assert_eq!(
+            intersection_length(&[0..=u64::MAX], &[0..=u64::MAX]),
+            1 << 64
+        );
+        assert_eq!(intersection_length(&[-5i32..=5], &[-10..=-3, 3..=4]), 5);
+    }
+
+    #[test]
+    fn sweep() {
+        use super::{Boundary, SweepEvent, sweep};
+        let ranges = [
+            4u32..=8,
+            1..=5,
+            10..=12,
+            8..=9,
+            std::ops::RangeInclusive::new(7, 6),
+        ];
+        let events = sweep(&ranges)
+            .map(
+                |SweepEvent {
+                     position,
+                     boundary,
+                     count,
+                 }| (position, boundary, count),
+            )
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            [
+                (1, Boundary::Start, 1),
+                (4, Boundary::Start, 2),
+                (5, Boundary::End, 1),
+                (8, Boundary::Start, 2),
+                (8, Boundary::End, 1),
+                (9, Boundary::End, 0),
+                (10, Boundary::Start, 1),
+                (12, Boundary::End, 0),
+            ]
+        );
+        // how many ranges cover each integer, according to the events
+        let coverage = |n: u32| {
+            events
+                .iter()
+                .take_while(|&&(position, boundary, _)| {
+                    position < n || (position == n && boundary == Boundary::Start)
+                })
+                .last()
+                .map_or(0, |&(_, _, count)| count)
+        };
+        for n in 0..15 {
+            let naive = ranges.iter().filter(|range| range.contains(&n)).count();
+            assert_eq!(coverage(n), naive, "{n}");
+        }
+        assert_eq!(coverage(3), 1);
+        assert_eq!(coverage(4), 2);
+        assert_eq!(coverage(8), 2);
+        // integers covered by at least two ranges
+        let mut doubly_covered = 0;
+        let mut since = None;
+        for &(position, boundary, count) in &events {
+            match (boundary, count) {
+                (Boundary::Start, 2) => since = Some(position),
+                (Boundary::End, 1) => doubly_covered += position - since.take().unwrap() + 1,
+                _ => {}
+            }
+        }
+        assert_eq!(doubly_covered, 3);
+    }
+
+    #[test]
+    fn len_inclusive() {
+        use super::len_inclusive;
+        assert_eq!(len_inclusive(&(3u64..=5)), 3);
+        assert_eq!(len_inclusive(&(0u64..=u64::MAX)), 1 << 64);
+        assert_eq!(len_inclusive(&(i32::MIN..=i32::MAX)), 1 << 32);
+        assert_eq!(len_inclusive(&(-2i8..=2)), 5);
+        assert_eq!(len_inclusive(&std::ops::RangeInclusive::new(5u8, 1)), 0);
+        let set = RangeSet::from_ranges([0u64..=u64::MAX, 5..=10]);
+        assert_eq!(set.len(), 1 << 64);
+        let set = RangeSet::from_ranges([10u64..=14, 3..=5, 16..=20, 12..=18]);
+        assert_eq!(set.len(), 3 + 11);
+        assert_eq!(RangeSet::<u32>::new().len(), 0);
+    }
+
+    #[test]
+    #[should_panic = "no gap"]
+    fn first_gap_at_max() {
+        let _ = super::first_gap(&[250u8..=255], 251);
+    }
+
+    #[test]
+    fn values() {
+        let set = RangeSet::from_ranges([1u32..=3, 2..=5, 8..=9]);
+        assert_eq!(set.values().collect::<Vec<_>>(), [1, 2, 3, 4, 5, 8, 9]);
+    }
+
+    #[test]
+    fn values_up_to_max() {
+        let set = RangeSet::from_ranges([250u8..=255, 253..=255]);
+        assert_eq!(
+            set.values().collect::<Vec<_>>(),
+            [250, 251, 252, 253, 254, 255]
+        );
+    }
+}
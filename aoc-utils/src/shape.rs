@@ -0,0 +1,77 @@
+//! Small fixed-size shapes, like puzzle pieces.
+//!
+//! A shape is a 3x3 grid of cells stored row by row, where `true` marks the
+//! cells that are part of the shape.
+
+/// Returns the shape rotated 90° clockwise.
+#[must_use]
+pub fn rotate_cw(shape: [bool; 9]) -> [bool; 9] {
+    std::array::from_fn(|i| {
+        let (row, col) = (i / 3, i % 3);
+        shape[(2 - col) * 3 + row]
+    })
+}
+
+/// Returns the shape mirrored left to right.
+#[must_use]
+pub fn flip(shape: [bool; 9]) -> [bool; 9] {
+    std::array::from_fn(|i| {
+        let (row, col) = (i / 3, i % 3);
+        shape[row * 3 + 2 - col]
+    })
+}
+
+/// Returns all the distinct orientations of the shape, i.e. its 4 rotations,
+/// with and without mirroring. Symmetric shapes have fewer than 8. The first
+/// one is always the shape itself.
+#[must_use]
+pub fn orientations(shape: [bool; 9]) -> Vec<[bool; 9]> {
+    let mut result = Vec::with_capacity(8);
+    for mut current in [shape, flip(shape)] {
+        for _ in 0..4 {
+            if !result.contains(&current) {
+                result.push(current);
+            }
+            current = rotate_cw(current);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(s: &[u8; 9]) -> [bool; 9] {
+        s.map(|c| c == b'#')
+    }
+
+    #[test]
+    fn rotate_and_flip() {
+        // rotating and flipping the L give different shapes
+        let l_shape = shape(b"#..#..##.");
+        assert_eq!(rotate_cw(l_shape), shape(b"####....."));
+        assert_eq!(rotate_cw(rotate_cw(l_shape)), shape(b".##..#..#"));
+        assert_eq!(flip(l_shape), shape(b"..#..#.##"));
+        let mut rotated = l_shape;
+        for _ in 0..4 {
+            rotated = rotate_cw(rotated);
+        }
+        assert_eq!(rotated, l_shape);
+        assert_eq!(flip(shape(b"##.#....#")), shape(b".##..##.."));
+        assert_eq!(flip(shape(b"##.......")), shape(b".##......"));
+    }
+
+    #[test]
+    fn orientations() {
+        assert_eq!(super::orientations(shape(b"#########")).len(), 1);
+        assert_eq!(super::orientations(shape(b".#.###.#.")).len(), 1);
+        assert_eq!(super::orientations(shape(b"###......")).len(), 4);
+        let l_shape = shape(b"#..#..##.");
+        let all = super::orientations(l_shape);
+        assert_eq!(all.len(), 8);
+        assert_eq!(all[0], l_shape);
+        // mirrored and upside down
+        assert!(all.contains(&shape(b"##.#..#..")));
+    }
+}
@@ -74,6 +74,29 @@ where
             self.result
         );
     }
+
+    /// Same as [`CorrectResultTest::test`], but if `stack_size` is given the
+    /// test runs on a new thread with a stack of that many bytes.
+    #[cfg_attr(not(test), allow(unused))]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn test_with_stack_size(self, stack_size: Option<usize>)
+    where
+        Self: Send,
+    {
+        let Some(stack_size) = stack_size else {
+            return self.test();
+        };
+        std::thread::scope(|scope| {
+            let handle = std::thread::Builder::new()
+                .stack_size(stack_size)
+                .spawn_scoped(scope, || self.test())
+                .expect("failed to spawn the test thread");
+            if let Err(panic) = handle.join() {
+                // keep the original assertion message
+                std::panic::resume_unwind(panic);
+            }
+        });
+    }
 }
 
 #[macro_export]
@@ -83,12 +106,16 @@ macro_rules! example_tests {
     // particular, the parser is always specified before the per-part example
     // data, but *after* the global example data. Make sure to check the order
     // of the parameters.
+    //
+    // An optional `stack_size: bytes,` right after the example data runs each
+    // solver on a thread with that stack size, for deeply recursive solvers.
     (
+        @stack ($stack_size:expr)
         $example_data:expr,
         $(
             parser: $per_part_parser:expr,
             $($per_part_example_data:literal,)?
-            $solver_name:ident => $result:expr
+            $solver_name:ident $(($($arg:expr),* $(,)?))? => $result:expr
         ),+
         $(,)?
     ) => {
@@ -108,69 +135,559 @@ macro_rules! example_tests {
                     {
                     CorrectResultTest {
                         parser,
-                        solver: super::$solver_name,
+                        solver: $crate::solver_with_args!(super::$solver_name $(, $($arg),*)?),
                         example: example_data.borrow(),
                         result: &$result,
                         marker: std::marker::PhantomData,
-                    }.test();
+                    }.test_with_stack_size($stack_size);
                 }
                 }
             )*
         }
     };
-    ($example_data:expr, $($solver_name:ident => $result:expr),+ $(,)?) => {
-        example_tests! {
+    (
+        @stack ($stack_size:expr)
+        $example_data:expr,
+        $($solver_name:ident $(($($arg:expr),* $(,)?))? => $result:expr),+
+        $(,)?
+    ) => {
+        $crate::example_tests! {
+            @stack ($stack_size)
             $example_data,
             $(
                 parser: super::parse,
-                $solver_name => $result
+                $solver_name $(($($arg),*))? => $result
             ),*
         }
     };
+    ($example_data:expr, stack_size: $stack_size:expr, $($rest:tt)+) => {
+        $crate::example_tests! { @stack (Some($stack_size)) $example_data, $($rest)+ }
+    };
+    ($example_data:expr, $($rest:tt)+) => {
+        $crate::example_tests! { @stack (None) $example_data, $($rest)+ }
+    };
 }
 
 #[macro_export]
 macro_rules! known_input_tests {
+    // Same as `example_tests!`, including the optional `stack_size: bytes,`
+    // right after the input.
+    //
+    // An optional `deny_placeholders,` after that (and after the stack size,
+    // if any) makes every expected result equal to its type's default (e.g.
+    // `=> 0`) fail the test as a forgotten placeholder, unless the entry is
+    // marked with `#[allow_zero]`. The expected results must then implement
+    // `Default`, `PartialEq` and `Debug`.
+    //
+    // Any other attribute on an entry, like `#[should_panic]`, is put on the
+    // generated test.
+    (@test ($mode:ident) [$($attrs:tt)*] [#[allow_zero] $($pending:tt)*] $($body:tt)*) => {
+        $crate::known_input_tests!(@test (allow_placeholders) [$($attrs)*] [$($pending)*] $($body)*);
+    };
+    (@test ($mode:ident) [$($attrs:tt)*] [#[$($attr:tt)*] $($pending:tt)*] $($body:tt)*) => {
+        $crate::known_input_tests!(@test ($mode) [$($attrs)* #[$($attr)*]] [$($pending)*] $($body)*);
+    };
     (
+        @test ($mode:ident) [$($attrs:tt)*] []
+        ($stack_size:expr) ($per_part_parser:expr) ($input:expr)
+        $solver_name:ident [$($args:tt)*] => $result:expr
+    ) => {
+        #[test]
+        $($attrs)*
+        fn $solver_name() {
+            use std::borrow::Borrow;
+            use $crate::testing::{CorrectResultTest, Unindentable};
+            #[allow(unused_variables)]
+            let parser = $per_part_parser;
+            let example_data = $input.unindent();
+            let result = &$result;
+            $crate::known_input_tests!(@check ($mode) result);
+            {
+            CorrectResultTest {
+                parser,
+                solver: $crate::solver_with_args!(super::$solver_name $($args)*),
+                example: example_data.borrow(),
+                result,
+                marker: std::marker::PhantomData,
+            }.test_with_stack_size($stack_size);
+        }
+        }
+    };
+    (@check (deny_placeholders) $result:ident) => {
+        $crate::testing::check_not_placeholder($result);
+    };
+    (@check ($mode:ident) $result:ident) => {};
+    (
+        @stack ($stack_size:expr) ($mode:ident)
         input: $input:expr,
         $(
             parser: $per_part_parser:expr,
-            $solver_name:ident => $result:expr
+            $(#[$($attr:tt)*])*
+            $solver_name:ident $(($($arg:expr),* $(,)?))? => $result:expr
         ),+
         $(,)?
     ) => {
         #[cfg(test)]
         mod known_input_tests {
+            $(
+                $crate::known_input_tests!(
+                    @test ($mode) [] [$(#[$($attr)*])*]
+                    ($stack_size) ($per_part_parser) ($input)
+                    $solver_name [$(, $($arg),*)?] => $result
+                );
+            )*
+        }
+    };
+    (
+        @stack ($stack_size:expr) ($mode:ident)
+        input: $input:expr,
+        $(
+            $(#[$($attr:tt)*])*
+            $solver_name:ident $(($($arg:expr),* $(,)?))? => $result:expr
+        ),+
+        $(,)?
+    ) => {
+        $crate::known_input_tests! {
+            @stack ($stack_size) ($mode)
+            input: $input,
+            $(
+                parser: super::parse,
+                $(#[$($attr)*])*
+                $solver_name $(($($arg),*))? => $result
+            ),*
+        }
+    };
+    (input: $input:expr, stack_size: $stack_size:expr, deny_placeholders, $($rest:tt)+) => {
+        $crate::known_input_tests! {
+            @stack (Some($stack_size)) (deny_placeholders) input: $input, $($rest)+
+        }
+    };
+    (input: $input:expr, stack_size: $stack_size:expr, $($rest:tt)+) => {
+        $crate::known_input_tests! {
+            @stack (Some($stack_size)) (allow_placeholders) input: $input, $($rest)+
+        }
+    };
+    (input: $input:expr, deny_placeholders, $($rest:tt)+) => {
+        $crate::known_input_tests! { @stack (None) (deny_placeholders) input: $input, $($rest)+ }
+    };
+    (input: $input:expr, $($rest:tt)+) => {
+        $crate::known_input_tests! { @stack (None) (allow_placeholders) input: $input, $($rest)+ }
+    };
+}
+
+/// Used by `known_input_tests!` with `deny_placeholders` to catch expected
+/// results that were left as placeholders, i.e. equal to the default value of their type.
+///
+/// # Panics
+///
+/// Panics if `expected` is the default value.
+#[doc(hidden)]
+pub fn check_not_placeholder<O: Default + PartialEq + std::fmt::Debug>(expected: &O) {
+    assert!(
+        *expected != O::default(),
+        "expected result {expected:?} looks like a placeholder, \
+         mark it with #[allow_zero] if it's the actual answer"
+    );
+}
+
+/// Declares both the `example_tests!` and the `known_input_tests!` of a day
+/// at once, listing each solver only once with its expected result for the
+/// example and for the known input:
+///
+/// ```text
+/// day_tests! {
+///     example: "...",
+///     input: include_str!("../input/2025/day1.txt"),
+///     part1 => (3, 1064),
+///     part2 => (6, 6122),
+/// }
+/// ```
+///
+/// Like in `known_input_tests!`, `deny_placeholders,` can follow the input,
+/// and attributes on an entry, including `#[allow_zero]`, apply to its known
+/// input test.
+#[macro_export]
+macro_rules! day_tests {
+    (
+        example: $example_data:expr,
+        input: $input:expr,
+        deny_placeholders,
+        $(
+            $(#[$($attr:tt)*])*
+            $solver_name:ident => ($example_result:expr, $known_result:expr)
+        ),+
+        $(,)?
+    ) => {
+        $crate::example_tests! {
+            $example_data,
+            $($solver_name => $example_result),+
+        }
+        $crate::known_input_tests! {
+            input: $input,
+            deny_placeholders,
+            $($(#[$($attr)*])* $solver_name => $known_result),+
+        }
+    };
+    (
+        example: $example_data:expr,
+        input: $input:expr,
+        $(
+            $(#[$($attr:tt)*])*
+            $solver_name:ident => ($example_result:expr, $known_result:expr)
+        ),+
+        $(,)?
+    ) => {
+        $crate::example_tests! {
+            $example_data,
+            $($solver_name => $example_result),+
+        }
+        $crate::known_input_tests! {
+            input: $input,
+            $($(#[$($attr)*])* $solver_name => $known_result),+
+        }
+    };
+}
+
+/// Used by the test macros to pass extra fixed arguments to a solver after
+/// its input, e.g. `part1(10) => 40` calls `part1(input, 10)`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! solver_with_args {
+    ($solver:path) => {
+        $solver
+    };
+    ($solver:path, $($arg:expr),*) => {
+        $crate::testing::bind_args($solver, ($($arg,)*))
+    };
+}
+
+/// A solver that takes some extra arguments after its input, as a tuple.
+pub trait SolverWithArgs<I: ?Sized, Args, R> {
+    fn call_with_args(self, input: &I, args: Args) -> R;
+}
+
+macro_rules! impl_solver_with_args {
+    ($(($($arg:ident),+)),+) => {
+        $(
+            impl<F, I, $($arg,)+ R> SolverWithArgs<I, ($($arg,)+), R> for F
+            where
+                F: FnOnce(&I, $($arg),+) -> R,
+                I: ?Sized,
+            {
+                #[allow(non_snake_case)]
+                fn call_with_args(self, input: &I, ($($arg,)+): ($($arg,)+)) -> R {
+                    self(input, $($arg),+)
+                }
+            }
+        )+
+    };
+}
+
+impl_solver_with_args!((A), (A, B), (A, B, C));
+
+/// Turns a solver with extra arguments into one that only takes the input.
+pub fn bind_args<I, Args, R>(
+    solver: impl SolverWithArgs<I, Args, R>,
+    args: Args,
+) -> impl FnOnce(&I) -> R
+where
+    I: ?Sized,
+{
+    move |input| solver.call_with_args(input, args)
+}
+
+/// Generates a named test for each small input of a single solver. Useful
+/// when there are many edge cases to cover and a separate
+/// [`example_tests!`] for each of them would be too noisy.
+///
+/// ```text
+/// cases! {
+///     parser: super::parse,
+///     solver: part1,
+///     empty: "" => 0,
+///     single: "L1" => 0,
+/// }
+/// ```
+///
+/// The tests are generated in a module named after the solver.
+#[macro_export]
+macro_rules! cases {
+    (
+        parser: $parser:expr,
+        solver: $solver_name:ident,
+        $($case_name:ident: $input:expr => $result:expr),+
+        $(,)?
+    ) => {
+        #[cfg(test)]
+        mod $solver_name {
             $(
                 #[test]
-                fn $solver_name() {
+                fn $case_name() {
                     use std::borrow::Borrow;
                     use $crate::testing::{CorrectResultTest, Unindentable};
-                    #[allow(unused_variables)]
-                    let parser = $per_part_parser;
+                    let parser = $parser;
                     let example_data = $input.unindent();
-                    {
                     CorrectResultTest {
                         parser,
                         solver: super::$solver_name,
                         example: example_data.borrow(),
                         result: &$result,
                         marker: std::marker::PhantomData,
-                    }.test();
+                    }
+                    .test();
                 }
-                }
-            )*
+            )+
         }
     };
-    (input: $input:expr, $($solver_name:ident => $result:expr),+ $(,)?) => {
-        known_input_tests! {
-            input: $input,
-            $(
-                parser: super::parse,
-                $solver_name => $result
-            ),*
+    (
+        solver: $solver_name:ident,
+        $($case_name:ident: $input:expr => $result:expr),+
+        $(,)?
+    ) => {
+        $crate::cases! {
+            parser: super::parse,
+            solver: $solver_name,
+            $($case_name: $input => $result),+
         }
     };
 }
 
-pub use {example_tests, known_input_tests};
+/// Records a named intermediate value of a solver, so that a test can
+/// [`capture`] it and compare it to a known snapshot.
+///
+/// The value is recorded with its `Debug` representation. Without the `trace`
+/// feature this does nothing, and the value is not even evaluated.
+#[cfg(feature = "trace")]
+#[macro_export]
+macro_rules! trace {
+    ($name:expr, $value:expr $(,)?) => {
+        $crate::testing::record_trace($name, &$value)
+    };
+}
+
+/// Records a named intermediate value of a solver, so that a test can
+/// capture it and compare it to a known snapshot.
+///
+/// The value is recorded with its `Debug` representation. Without the `trace`
+/// feature this does nothing, and the value is not even evaluated.
+#[cfg(not(feature = "trace"))]
+#[macro_export]
+macro_rules! trace {
+    ($name:expr, $value:expr $(,)?) => {{
+        let _ = || (&$name, &$value);
+    }};
+}
+
+#[cfg(feature = "trace")]
+std::thread_local! {
+    static TRACES: std::cell::RefCell<Option<Traces>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Values recorded with [`trace!`] while running [`capture`].
+#[cfg(feature = "trace")]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Traces {
+    entries: Vec<(&'static str, String)>,
+}
+
+#[cfg(feature = "trace")]
+impl Traces {
+    /// All the recorded values, in order.
+    #[must_use]
+    pub fn entries(&self) -> &[(&'static str, String)] {
+        &self.entries
+    }
+
+    /// The last value recorded with the given name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(entry, _)| *entry == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[cfg(feature = "trace")]
+#[doc(hidden)]
+pub fn record_trace(name: &'static str, value: &dyn std::fmt::Debug) {
+    TRACES.with_borrow_mut(|traces| {
+        if let Some(traces) = traces {
+            traces.entries.push((name, format!("{value:?}")));
+        }
+    });
+}
+
+/// Runs `f` and collects every value it records with [`trace!`] on the
+/// current thread. Values traced outside of `capture` are discarded.
+#[cfg(feature = "trace")]
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, Traces) {
+    let previous = TRACES.replace(Some(Traces::default()));
+    let result = f();
+    let traces = TRACES.replace(previous).unwrap_or_default();
+    (result, traces)
+}
+
+pub use {cases, day_tests, example_tests, known_input_tests, solver_with_args, trace};
+
+#[cfg(test)]
+mod tests {
+    fn parse(input: &str) -> Vec<u32> {
+        input.lines().map(|line| line.parse().unwrap()).collect()
+    }
+
+    fn sum(input: &[u32]) -> u32 {
+        input.iter().sum()
+    }
+
+    fn sum_of_largest(input: &[u32], k: usize) -> u32 {
+        let mut input = input.to_vec();
+        input.sort_unstable_by(|a, b| b.cmp(a));
+        input.iter().take(k).sum()
+    }
+
+    fn sum_in_range(input: &[u32], min: u32, max: u32) -> u32 {
+        input.iter().filter(|&&x| (min..=max).contains(&x)).sum()
+    }
+
+    crate::example_tests! {
+        "
+        5
+        1
+        4
+        2
+        ",
+        sum => 12,
+        sum_of_largest(2) => 9,
+        sum_in_range(2, 4) => 6,
+    }
+
+    mod both {
+        use super::{parse, sum};
+
+        fn max(input: &[u32]) -> u32 {
+            input.iter().copied().max().unwrap()
+        }
+
+        crate::day_tests! {
+            example: "
+            5
+            1
+            4
+            ",
+            input: "10\n20\n30\n",
+            sum => (10, 60),
+            max => (5, 30),
+        }
+    }
+
+    mod zero {
+        use super::parse;
+
+        fn count_zeroes(input: &[u32]) -> usize {
+            input.iter().filter(|&&x| x == 0).count()
+        }
+
+        crate::known_input_tests! {
+            input: "1\n2\n3",
+            deny_placeholders,
+            #[allow_zero]
+            count_zeroes => 0,
+        }
+
+        mod placeholder {
+            use super::{count_zeroes, parse};
+
+            crate::known_input_tests! {
+                input: "1\n2\n3",
+                deny_placeholders,
+                #[should_panic = "looks like a placeholder"]
+                count_zeroes => 0,
+            }
+        }
+
+        mod both {
+            use super::{count_zeroes, parse};
+
+            crate::day_tests! {
+                example: "0\n1",
+                input: "1\n2\n3",
+                deny_placeholders,
+                #[allow_zero]
+                count_zeroes => (1, 0),
+            }
+        }
+
+        mod not_checked {
+            use super::{count_zeroes, parse};
+
+            crate::known_input_tests! {
+                input: "1\n2\n3",
+                count_zeroes => 0,
+            }
+        }
+
+        #[test]
+        fn not_placeholder() {
+            crate::testing::check_not_placeholder(&42u64);
+            crate::testing::check_not_placeholder(&"answer");
+        }
+    }
+
+    mod deep {
+        use super::parse;
+
+        // not tail recursive, so that each element takes a stack frame
+        fn sum_recursive(input: &[u32]) -> u64 {
+            match input {
+                [] => 0,
+                [first, rest @ ..] => u64::from(*first) + sum_recursive(rest),
+            }
+        }
+
+        fn deep_sum(input: &[u32]) -> u64 {
+            let repeated = input.repeat(100_000);
+            sum_recursive(&repeated)
+        }
+
+        crate::example_tests! {
+            "
+            1
+            2
+            ",
+            stack_size: 256 << 20,
+            deep_sum => 300_000,
+        }
+    }
+
+    crate::cases! {
+        solver: sum,
+        empty: "" => 0,
+        single: "42" => 42,
+        several: "
+            1
+            2
+            3
+        " => 6,
+    }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn capture_trace() {
+        fn traced_sum(input: &[u32]) -> u32 {
+            let doubled = input.iter().map(|x| x * 2).collect::<Vec<_>>();
+            crate::trace!("doubled", doubled);
+            doubled.iter().sum()
+        }
+
+        let (result, traces) = super::capture(|| traced_sum(&[1, 2, 3]));
+        assert_eq!(result, 12);
+        assert_eq!(traces.get("doubled"), Some("[2, 4, 6]"));
+        assert_eq!(traces.get("missing"), None);
+        // nothing is recorded outside of capture
+        traced_sum(&[4]);
+        let ((), traces) = super::capture(|| {});
+        assert!(traces.entries().is_empty());
+    }
+}
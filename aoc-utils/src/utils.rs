@@ -33,22 +33,259 @@ impl<'a, T> Iterator for PairsIterator<'a, T> {
             None
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.slice.len();
+        if self.index1 >= len {
+            return (0, Some(0));
+        }
+        // what's left of the current row, plus all the pairs among the
+        // elements after index1
+        let rest = len - self.index1 - 1;
+        let remaining = len.saturating_sub(self.index2) + rest * rest.saturating_sub(1) / 2;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for PairsIterator<'_, T> {}
+
+/// Iterate over all the orderings of the elements in a slice, using Heap's
+/// algorithm
+pub struct PermutationsIterator<'a, T> {
+    items: Vec<&'a T>,
+    counters: Vec<usize>,
+    index: usize,
+    started: bool,
+}
+
+impl<'a, T> PermutationsIterator<'a, T> {
+    fn new(slice: &'a [T]) -> Self {
+        Self {
+            items: slice.iter().collect(),
+            counters: vec![0; slice.len()],
+            index: 1,
+            started: false,
+        }
+    }
+}
+
+impl<'a, T> Iterator for PermutationsIterator<'a, T> {
+    type Item = Vec<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            return Some(self.items.clone());
+        }
+        while self.index < self.items.len() {
+            if self.counters[self.index] < self.index {
+                let other = if self.index.is_multiple_of(2) {
+                    0
+                } else {
+                    self.counters[self.index]
+                };
+                self.items.swap(other, self.index);
+                self.counters[self.index] += 1;
+                self.index = 1;
+                return Some(self.items.clone());
+            }
+            self.counters[self.index] = 0;
+            self.index += 1;
+        }
+        None
+    }
 }
 
 pub trait SliceUtils<T> {
+    /// Iterates over all the `n!` orderings of the elements. An empty slice
+    /// has exactly one (empty) ordering.
+    fn permutations(&self) -> PermutationsIterator<'_, T>;
+
     fn pairs(&self) -> PairsIterator<'_, T>;
+
+    /// Iterates over the indices `(i, j)` of all unique pairs of elements,
+    /// with `i < j`, in the same order as [`SliceUtils::pairs`].
+    fn index_pairs(&self) -> impl Iterator<Item = (usize, usize)>;
+
+    /// Iterates over all the ordered pairs of elements at distinct indices, so
+    /// both `(a, b)` and `(b, a)`, for `n * (n - 1)` pairs in total.
+    fn ordered_pairs<'a>(&'a self) -> impl Iterator<Item = (&'a T, &'a T)>
+    where
+        T: 'a;
+
+    /// Like `is_sorted_by_key`, but on failure returns the index of the first
+    /// element whose key is smaller than the previous one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(index)` if the slice is not sorted in ascending order.
+    fn check_sorted_by_key<K: PartialOrd>(&self, key: impl FnMut(&T) -> K) -> Result<(), usize>;
+
+    /// Same as [`SliceUtils::check_sorted_by_key`], but for descending order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(index)` if the slice is not sorted in descending order.
+    fn check_sorted_desc_by_key<K: PartialOrd>(
+        &self,
+        key: impl FnMut(&T) -> K,
+    ) -> Result<(), usize>;
+
+    /// Returns the maximum of each window of `window` consecutive elements, in
+    /// O(n) using a monotonic deque. Returns an empty vector if `window` is 0
+    /// or larger than the slice.
+    fn sliding_max(&self, window: usize) -> Vec<T>
+    where
+        T: Ord + Clone;
+
+    /// Iterates over all the contiguous windows of length `n`, like
+    /// `<[T]>::windows`. Yields nothing if `n` is 0 or larger than the slice.
+    fn windows_of<'a>(&'a self, n: usize) -> impl Iterator<Item = &'a [T]>
+    where
+        T: 'a;
+
+    /// Iterates over consecutive chunks of length `n`, like
+    /// `<[T]>::chunks_exact`. Elements at the end that don't make up a full
+    /// chunk are dropped. Yields nothing if `n` is 0.
+    fn chunks_exact_of<'a>(&'a self, n: usize) -> impl Iterator<Item = &'a [T]>
+    where
+        T: 'a;
+
+    /// Index of the maximum element, picking the first one if there are ties.
+    fn arg_max_first(&self) -> Option<usize>
+    where
+        T: Ord;
+
+    /// Index of the maximum element, picking the last one if there are ties.
+    fn arg_max_last(&self) -> Option<usize>
+    where
+        T: Ord;
+}
+
+fn first_unsorted_index<T, K>(
+    slice: &[T],
+    mut key: impl FnMut(&T) -> K,
+    in_order: impl Fn(&K, &K) -> bool,
+) -> Result<(), usize> {
+    let mut keys = slice.iter().map(&mut key);
+    let Some(mut previous) = keys.next() else {
+        return Ok(());
+    };
+    for (index, current) in keys.enumerate() {
+        if !in_order(&previous, &current) {
+            return Err(index + 1);
+        }
+        previous = current;
+    }
+    Ok(())
 }
 
 impl<T> SliceUtils<T> for [T] {
     fn pairs(&self) -> PairsIterator<'_, T> {
         PairsIterator::new(self)
     }
+
+    fn permutations(&self) -> PermutationsIterator<'_, T> {
+        PermutationsIterator::new(self)
+    }
+
+    fn index_pairs(&self) -> impl Iterator<Item = (usize, usize)> {
+        let len = self.len();
+        (0..len).flat_map(move |i| (i + 1..len).map(move |j| (i, j)))
+    }
+
+    fn ordered_pairs<'a>(&'a self) -> impl Iterator<Item = (&'a T, &'a T)>
+    where
+        T: 'a,
+    {
+        self.iter().enumerate().flat_map(move |(i, a)| {
+            self.iter()
+                .enumerate()
+                .filter(move |&(j, _)| i != j)
+                .map(move |(_, b)| (a, b))
+        })
+    }
+
+    fn check_sorted_by_key<K: PartialOrd>(&self, key: impl FnMut(&T) -> K) -> Result<(), usize> {
+        first_unsorted_index(self, key, |a, b| a <= b)
+    }
+
+    fn check_sorted_desc_by_key<K: PartialOrd>(
+        &self,
+        key: impl FnMut(&T) -> K,
+    ) -> Result<(), usize> {
+        first_unsorted_index(self, key, |a, b| a >= b)
+    }
+
+    fn sliding_max(&self, window: usize) -> Vec<T>
+    where
+        T: Ord + Clone,
+    {
+        if window == 0 || window > self.len() {
+            return Vec::new();
+        }
+        let mut result = Vec::with_capacity(self.len() - window + 1);
+        // indices of the candidate maximums, with decreasing values
+        let mut candidates = std::collections::VecDeque::with_capacity(window);
+        for (index, value) in self.iter().enumerate() {
+            while candidates.back().is_some_and(|&last| self[last] <= *value) {
+                candidates.pop_back();
+            }
+            candidates.push_back(index);
+            if candidates[0] + window <= index {
+                candidates.pop_front();
+            }
+            if index + 1 >= window {
+                result.push(self[candidates[0]].clone());
+            }
+        }
+        result
+    }
+
+    fn windows_of<'a>(&'a self, n: usize) -> impl Iterator<Item = &'a [T]>
+    where
+        T: 'a,
+    {
+        // windows(0) would panic
+        self.windows(n.max(1)).filter(move |_| n > 0)
+    }
+
+    fn chunks_exact_of<'a>(&'a self, n: usize) -> impl Iterator<Item = &'a [T]>
+    where
+        T: 'a,
+    {
+        // chunks_exact(0) would panic
+        self.chunks_exact(n.max(1)).filter(move |_| n > 0)
+    }
+
+    fn arg_max_first(&self) -> Option<usize>
+    where
+        T: Ord,
+    {
+        // max_by_key returns the last of equal elements, so go backwards
+        self.iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|&(_, value)| value)
+            .map(|(index, _)| index)
+    }
+
+    fn arg_max_last(&self) -> Option<usize>
+    where
+        T: Ord,
+    {
+        self.iter()
+            .enumerate()
+            .max_by_key(|&(_, value)| value)
+            .map(|(index, _)| index)
+    }
 }
 
 /// Extensions to [[u8]] for ASCII-specific operations
 pub trait AsciiUtils<'a> {
     type Lines: Iterator<Item = &'a [u8]>;
-    /// Iterate over the lines in a slice of ASCII bytes
+    /// Iterate over the lines in a slice of ASCII bytes, without the `\n` or
+    /// `\r\n` at the end of each line
     fn ascii_lines(&self) -> Self::Lines;
 
     /// Parses this byte slice into another type as an ASCII string.
@@ -67,21 +304,55 @@ pub trait AsciiUtils<'a> {
         F::from_ascii(self)
     }
 
+    /// Splits the slice at the first occurrence of `sep` and parses both sides,
+    /// e.g. `b"3-5"` with `b'-'`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `sep` is missing or if either side can't be parsed
+    /// into the desired type.
+    fn parse_pair<A, B>(self, sep: u8) -> Result<(A, B), ParsePairError<A::Error, B::Error>>
+    where
+        A: FromAscii<Slice<'a> = &'a [u8]>,
+        B: FromAscii<Slice<'a> = &'a [u8]>;
+
+    /// Iterate over the blocks of lines separated by one or more blank lines,
+    /// without the newlines around them. Works with both `\n` and `\r\n`
+    /// line endings.
+    fn sections(&self) -> SectionsIterator<'a>;
+
+    /// Parses every run of ASCII digits in the slice, skipping whatever is in
+    /// between, e.g. `[57, 618, 57]` from `b"57,618,57"`. A minus sign is
+    /// never part of a number; see [`AsciiUtils::parse_signed_numbers`].
+    fn parse_numbers<T>(&self) -> NumbersIterator<'a, T>
+    where
+        T: FromAscii<Slice<'a> = &'a [u8]>;
+
+    /// Like [`AsciiUtils::parse_numbers`], but a `-` right before the digits
+    /// makes the number negative, so `b"x=-3"` gives `-3`.
+    fn parse_signed_numbers<T>(&self) -> NumbersIterator<'a, T>
+    where
+        T: FromAscii<Slice<'a> = &'a [u8]>;
+
     /// Interpret the slice as a grid of cells that can be converted from ASCII
-    /// characters, where each line is the same length.
+    /// characters, where each line is the same length. Blank lines at the end
+    /// are not part of the grid.
     ///
     /// # Errors
     ///
     /// Will return `Err` if it’s not possible to parse every byte into the
     /// desired cell type.
     fn grid_like<Cell: TryFrom<u8>>(&self) -> Result<GridLike<Cell>, Cell::Error> {
-        // TODO: probably not optimized
-        let cells = self
-            .ascii_lines()
+        let mut lines = self.ascii_lines().collect::<Vec<_>>();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+        let cells = lines
+            .iter()
             .flat_map(|line| line.iter().map(|&c| c.try_into()))
             .collect::<Result<Vec<Cell>, Cell::Error>>()?;
-        let width = self.ascii_lines().next().map_or(0, <[u8]>::len);
-        let height = self.ascii_lines().count();
+        let width = lines.first().map_or(0, |line| line.len());
+        let height = lines.len();
         Ok(GridLike {
             cells,
             width,
@@ -95,6 +366,99 @@ impl<'a> AsciiUtils<'a> for &'a [u8] {
     fn ascii_lines(&self) -> LinesIterator<'a> {
         LinesIterator::new(self)
     }
+
+    fn sections(&self) -> SectionsIterator<'a> {
+        SectionsIterator::new(self)
+    }
+
+    fn parse_numbers<T>(&self) -> NumbersIterator<'a, T>
+    where
+        T: FromAscii<Slice<'a> = &'a [u8]>,
+    {
+        NumbersIterator::new(self, false)
+    }
+
+    fn parse_signed_numbers<T>(&self) -> NumbersIterator<'a, T>
+    where
+        T: FromAscii<Slice<'a> = &'a [u8]>,
+    {
+        NumbersIterator::new(self, true)
+    }
+
+    fn parse_pair<A, B>(self, sep: u8) -> Result<(A, B), ParsePairError<A::Error, B::Error>>
+    where
+        A: FromAscii<Slice<'a> = &'a [u8]>,
+        B: FromAscii<Slice<'a> = &'a [u8]>,
+    {
+        let index = self
+            .iter()
+            .position(|&c| c == sep)
+            .ok_or(ParsePairError::MissingSeparator)?;
+        let first = A::from_ascii(&self[..index]).map_err(ParsePairError::First)?;
+        let second = B::from_ascii(&self[index + 1..]).map_err(ParsePairError::Second)?;
+        Ok((first, second))
+    }
+}
+
+/// Extensions to [str] for text where cells are whole `char`s, not bytes.
+pub trait StrUtils {
+    /// Like [`AsciiUtils::grid_like`], but each cell is a `char`, so that
+    /// grids with multibyte characters are split into the right columns.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if it’s not possible to parse every character into
+    /// the desired cell type.
+    fn char_grid<Cell: TryFrom<char>>(&self) -> Result<GridLike<Cell>, Cell::Error>;
+}
+
+impl StrUtils for str {
+    fn char_grid<Cell: TryFrom<char>>(&self) -> Result<GridLike<Cell>, Cell::Error> {
+        let mut lines = self.lines().collect::<Vec<_>>();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+        let cells = lines
+            .iter()
+            .flat_map(|line| line.chars().map(Cell::try_from))
+            .collect::<Result<Vec<Cell>, Cell::Error>>()?;
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        let height = lines.len();
+        Ok(GridLike {
+            cells,
+            width,
+            height,
+        })
+    }
+}
+
+/// Error returned by [`AsciiUtils::parse_pair`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsePairError<A, B> {
+    MissingSeparator,
+    First(A),
+    Second(B),
+}
+
+/// Parses a token made of a direction character followed by a magnitude, like
+/// `R14` or `L68`, into a signed number: positive after `positive`, negative
+/// after `negative`. Returns `None` for any other prefix or if the magnitude
+/// is not a plain run of digits that fits in an `i64`.
+#[must_use]
+pub fn parse_signed_token(s: &str, positive: char, negative: char) -> Option<i64> {
+    let mut chars = s.chars();
+    let sign = match chars.next()? {
+        c if c == positive => 1,
+        c if c == negative => -1,
+        _ => return None,
+    };
+    let digits = chars.as_str();
+    // `u64::from_str` would accept a `+` here
+    if !digits.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let magnitude: u64 = digits.parse().ok()?;
+    Some(sign * i64::try_from(magnitude).ok()?)
 }
 
 /// Iterate over the lines in a slice of ASCII bytes
@@ -118,7 +482,11 @@ impl<'a> Iterator for LinesIterator<'a> {
             let slice = &self.slice[start..];
             let end = if let Some(newline) = slice.iter().position(|&c| c == b'\n') {
                 self.index += newline + 1;
-                start + newline
+                if slice[..newline].ends_with(b"\r") {
+                    start + newline - 1
+                } else {
+                    start + newline
+                }
             } else {
                 self.index = self.slice.len();
                 self.slice.len()
@@ -130,6 +498,89 @@ impl<'a> Iterator for LinesIterator<'a> {
     }
 }
 
+/// Iterate over the blank-line separated sections in a slice of ASCII bytes
+pub struct SectionsIterator<'a> {
+    slice: &'a [u8],
+    index: usize,
+}
+
+impl<'a> SectionsIterator<'a> {
+    fn new(slice: &'a [u8]) -> Self {
+        Self { slice, index: 0 }
+    }
+}
+
+impl<'a> Iterator for SectionsIterator<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut section: Option<(usize, usize)> = None;
+        while self.index < self.slice.len() {
+            let line_start = self.index;
+            let line_end = self.slice[line_start..]
+                .iter()
+                .position(|&c| c == b'\n')
+                .map_or(self.slice.len(), |newline| line_start + newline);
+            let line = &self.slice[line_start..line_end];
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if line.is_empty() {
+                if section.is_some() {
+                    break;
+                }
+            } else {
+                let start = section.map_or(line_start, |(start, _)| start);
+                section = Some((start, line_start + line.len()));
+            }
+            self.index = (line_end + 1).min(self.slice.len());
+        }
+        section.map(|(start, end)| &self.slice[start..end])
+    }
+}
+
+/// Iterate over the numbers in a slice of ASCII bytes, see
+/// [`AsciiUtils::parse_numbers`].
+pub struct NumbersIterator<'a, T> {
+    slice: &'a [u8],
+    index: usize,
+    signed: bool,
+    marker: std::marker::PhantomData<T>,
+}
+
+impl<'a, T> NumbersIterator<'a, T> {
+    fn new(slice: &'a [u8], signed: bool) -> Self {
+        Self {
+            slice,
+            index: 0,
+            signed,
+            marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Iterator for NumbersIterator<'a, T>
+where
+    T: FromAscii<Slice<'a> = &'a [u8]>,
+{
+    type Item = Result<T, T::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.slice[self.index..];
+        let digits_start = self.index + rest.iter().position(u8::is_ascii_digit)?;
+        let digits_len = self.slice[digits_start..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .count();
+        let start =
+            if self.signed && digits_start > self.index && self.slice[digits_start - 1] == b'-' {
+                digits_start - 1
+            } else {
+                digits_start
+            };
+        self.index = digits_start + digits_len;
+        Some(T::from_ascii(&self.slice[start..self.index]))
+    }
+}
+
 /// Similar to `FromStr`, but for ASCII bytes
 pub trait FromAscii: Sized {
     type Slice<'a>;
@@ -178,7 +629,6 @@ impl<Cell> GridLike<Cell> {
     pub fn into_grid<G>(self) -> G
     where
         G: FromGridLike<Cell = Cell>,
-        Cell: TryFrom<u8>,
     {
         let GridLike {
             cells,
@@ -187,16 +637,69 @@ impl<Cell> GridLike<Cell> {
         } = self;
         G::from_cells(cells, width, height)
     }
+
+    /// The `(x, y)` positions of all the cells matching `predicate`, in row
+    /// order. Useful to pick out markers like a start position while parsing.
+    pub fn find_cells(&self, mut predicate: impl FnMut(&Cell) -> bool) -> Vec<(usize, usize)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|&(_, cell)| predicate(cell))
+            .map(|(index, _)| (index % self.width, index / self.width))
+            .collect()
+    }
+
+    /// The `(x, y)` position of the first cell matching `predicate`, in row
+    /// order.
+    pub fn find_cell(&self, predicate: impl FnMut(&Cell) -> bool) -> Option<(usize, usize)> {
+        let index = self.cells.iter().position(predicate)?;
+        Some((index % self.width, index / self.width))
+    }
+
+    /// Surrounds the grid with a border of `fill` cells, `border` cells thick
+    /// on each side. Useful to avoid bounds checks when looking at neighbors.
+    #[must_use]
+    pub fn padded(self, border: usize, fill: Cell) -> GridLike<Cell>
+    where
+        Cell: Clone,
+    {
+        let width = self.width + 2 * border;
+        let height = self.height + 2 * border;
+        let mut cells = Vec::with_capacity(width * height);
+        cells.resize(border * width, fill.clone());
+        for row in self.cells.chunks(self.width.max(1)) {
+            cells.resize(cells.len() + border, fill.clone());
+            cells.extend_from_slice(row);
+            cells.resize(cells.len() + border, fill.clone());
+        }
+        cells.resize(width * height, fill);
+        GridLike {
+            cells,
+            width,
+            height,
+        }
+    }
 }
 
 pub trait FromGridLike
 where
     Self: Sized,
 {
-    type Cell: TryFrom<u8>;
+    type Cell;
     fn from_cells(cells: Vec<Self::Cell>, width: usize, height: usize) -> Self;
 }
 
+/// A nested vector of rows, so that cells can be accessed as `grid[y][x]`.
+impl<Cell> FromGridLike for Vec<Vec<Cell>> {
+    type Cell = Cell;
+    fn from_cells(cells: Vec<Cell>, width: usize, height: usize) -> Self {
+        let mut cells = cells.into_iter();
+        (0..height)
+            .map(|_| cells.by_ref().take(width).collect())
+            .collect()
+    }
+}
+
 pub struct InvalidCharacter(pub u8);
 
 impl core::fmt::Debug for InvalidCharacter {
@@ -205,18 +708,26 @@ impl core::fmt::Debug for InvalidCharacter {
     }
 }
 
+/// Defines an enum of grid cells, each parsed from a single byte.
+///
+/// An optional last `_ => Variant => byte` arm maps every byte not listed
+/// above to `Variant`, instead of failing with [`InvalidCharacter`]. That
+/// variant is displayed as `byte`.
 #[macro_export]
 macro_rules! grid_cell_enum {
     (
         $(#[$attrs:meta])*
         enum $name:ident {
-            $($variant:ident => $value:expr),*$(,)?
+            $($variant:ident => $value:expr),*
+            $(, _ => $default:ident => $default_value:expr)?
+            $(,)?
         }
     )
         => {
             $(#[$attrs])*
             enum $name {
                 $($variant,)*
+                $($default,)?
             }
 
             impl TryFrom<u8> for $name {
@@ -224,7 +735,7 @@ macro_rules! grid_cell_enum {
                 fn try_from(c: u8) -> Result<Self, $crate::utils::InvalidCharacter> {
                     match c {
                         $($value => Ok(Self::$variant),)*
-                        c => Err($crate::utils::InvalidCharacter(c)),
+                        c => $crate::grid_cell_enum!(@fallback c $(, $default)?),
                     }
                 }
             }
@@ -233,10 +744,34 @@ macro_rules! grid_cell_enum {
                 fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
                     match self {
                         $($name::$variant => write!(f, "{}", $value as char),)*
+                        $($name::$default => write!(f, "{}", $default_value as char),)?
                     }
                 }
             }
-        }
+
+            impl $name {
+                /// Every variant, in declaration order.
+                #[allow(dead_code)]
+                const ALL: [Self; [$(stringify!($variant),)* $(stringify!($default),)?].len()] =
+                    [$(Self::$variant,)* $(Self::$default,)?];
+
+                /// The byte this cell is parsed from.
+                #[allow(dead_code)]
+                const fn byte(self) -> u8 {
+                    match self {
+                        $($name::$variant => $value,)*
+                        $($name::$default => $default_value,)?
+                    }
+                }
+            }
+        };
+    (@fallback $c:ident) => {
+        Err($crate::utils::InvalidCharacter($c))
+    };
+    (@fallback $c:ident, $default:ident) => {{
+        let _ = $c;
+        Ok(Self::$default)
+    }};
 }
 
 pub use grid_cell_enum;
@@ -247,6 +782,30 @@ pub enum Parity {
     Odd = 1,
 }
 
+impl Parity {
+    /// The parity of a count of items.
+    #[must_use]
+    pub const fn from_count(n: usize) -> Self {
+        if n & 1 == 0 {
+            Parity::Even
+        } else {
+            Parity::Odd
+        }
+    }
+}
+
+impl std::ops::BitXor for Parity {
+    type Output = Self;
+    /// The parity of the sum of two numbers with the given parities.
+    fn bitxor(self, other: Self) -> Self::Output {
+        if self == other {
+            Parity::Even
+        } else {
+            Parity::Odd
+        }
+    }
+}
+
 impl std::ops::Not for Parity {
     type Output = Self;
     fn not(self) -> Self::Output {
@@ -264,8 +823,112 @@ pub trait NumberExt: Sized {
     fn least_common_multiple(self, other: Self) -> Self;
     #[must_use]
     fn parity(self) -> Parity;
+    /// Splits the number into two halves that add up to it, rounding the
+    /// first one away from zero and the second one towards zero: 7 gives
+    /// `(4, 3)`, 8 gives `(4, 4)`, and -7 gives `(-4, -3)`.
+    #[must_use]
+    fn halves(self) -> (Self, Self);
+    /// Same as [`NumberExt::greatest_common_divisor`], which can't overflow;
+    /// this is only here to pair with [`NumberExt::checked_lcm`].
+    #[must_use]
+    fn checked_gcd(self, other: Self) -> Option<Self> {
+        Some(self.greatest_common_divisor(other))
+    }
+    /// The least common multiple, or `None` if it doesn't fit in the type or
+    /// if either argument is negative. Unlike
+    /// [`NumberExt::least_common_multiple`], this never overflows when the
+    /// result fits.
+    #[must_use]
+    fn checked_lcm(self, other: Self) -> Option<Self>
+    where
+        Self: TryInto<u128> + TryFrom<u128>,
+    {
+        let a: u128 = self.try_into().ok()?;
+        let b: u128 = other.try_into().ok()?;
+        if a == 0 || b == 0 {
+            return Some(Self::zero());
+        }
+        let lcm = (a / a.greatest_common_divisor(b)).checked_mul(b)?;
+        Self::try_from(lcm).ok()
+    }
+    /// The binomial coefficient: the number of ways to choose `k` items out
+    /// of `n`. Returns `None` if the result doesn't fit in the type, or if
+    /// either argument is negative.
+    #[must_use]
+    fn choose(n: Self, k: Self) -> Option<Self>
+    where
+        Self: TryInto<u128> + TryFrom<u128>,
+    {
+        let n: u128 = n.try_into().ok()?;
+        let k: u128 = k.try_into().ok()?;
+        if k > n {
+            return Some(Self::zero());
+        }
+        let k = k.min(n - k);
+        // after each step, result is C(n - k + i, i), so it never exceeds the
+        // final value
+        let mut result: u128 = 1;
+        for i in 1..=k {
+            // result * (n - k + i) is divisible by i, so dividing both
+            // factors by their common divisors first keeps it exact
+            let divisor = result.greatest_common_divisor(i);
+            result = (result / divisor).checked_mul((n - k + i) / (i / divisor))?;
+        }
+        Self::try_from(result).ok()
+    }
+
+    /// The integer square root, rounded down.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number is negative.
+    #[must_use]
+    fn integer_sqrt(self) -> Self
+    where
+        Self: TryInto<u128> + TryFrom<u128>,
+    {
+        let Ok(n): Result<u128, _> = self.try_into() else {
+            panic!("square root of a negative number");
+        };
+        // the root is never larger than the number itself, so it fits
+        Self::try_from(n.isqrt()).ok().unwrap()
+    }
+
+    /// The integer square root, rounded up. This is the smallest `r` such
+    /// that `r * r >= self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number is negative.
+    #[must_use]
+    fn sqrt_ceil(self) -> Self
+    where
+        Self: TryInto<u128> + TryFrom<u128>,
+    {
+        let Ok(n): Result<u128, _> = self.try_into() else {
+            panic!("square root of a negative number");
+        };
+        let root = n.isqrt();
+        let root = if root * root < n { root + 1 } else { root };
+        Self::try_from(root).ok().unwrap()
+    }
+
+    /// The absolute difference `|self - other|`, in the same type.
+    ///
+    /// Unlike the inherent `abs_diff` on primitive integers, the result has
+    /// the same type as the operands also for signed types, so it can
+    /// overflow, e.g. for `i8::MIN` and `i8::MAX`.
     #[must_use]
-    fn split_odd_even(self) -> (Self, Self);
+    fn abs_difference(self, other: Self) -> Self
+    where
+        Self: PartialOrd + core::ops::Sub<Output = Self>,
+    {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
 
     #[must_use]
     fn zero() -> Self;
@@ -301,11 +964,10 @@ where
         }
     }
 
-    fn split_odd_even(self) -> (Self, Self) {
+    fn halves(self) -> (Self, Self) {
         let two = Self::one() + Self::one();
-        let even = self / two;
-        let odd = even + (self % two);
-        (odd, even)
+        let toward_zero = self / two;
+        (toward_zero + self % two, toward_zero)
     }
 
     fn greatest_common_divisor(self, other: Self) -> Self {
@@ -320,23 +982,142 @@ where
     }
 
     fn least_common_multiple(self, other: Self) -> Self {
-        self * other / self.greatest_common_divisor(other)
+        self / self.greatest_common_divisor(other) * other
     }
 }
 
-pub trait NumberIteratorExt: Sized {
-    fn least_common_multiple(self) -> Self::Item
-    where
-        Self: Iterator,
-        Self::Item: NumberExt,
-    {
-        self.fold(Self::Item::one(), Self::Item::least_common_multiple)
-    }
+/// Zero and one as associated constants, for the generic code that needs them
+/// in a const context, where [`NumberExt::zero`] and [`NumberExt::one`] can't
+/// be used.
+pub trait ConstNumber {
+    const ZERO: Self;
+    const ONE: Self;
 }
 
-impl<T> NumberIteratorExt for T where T: Iterator {}
-
-pub trait NumberDigitsExt: Copy {
+macro_rules! impl_const_number {
+    ($($t:ty),+) => {
+        $(
+            impl ConstNumber for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+            }
+        )+
+    };
+}
+
+impl_const_number!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+/// Iterates over the indices of the set bits of `n`, from the least
+/// significant one.
+pub fn set_bits(n: u64) -> impl Iterator<Item = u32> {
+    let mut bits = n;
+    std::iter::from_fn(move || {
+        (bits != 0).then(|| {
+            let index = bits.trailing_zeros();
+            // clear the lowest set bit
+            bits &= bits - 1;
+            index
+        })
+    })
+}
+
+/// Combines all the items with XOR. When each item is the set of toggles
+/// made by one action (like pressing a button that flips some lights), this
+/// is the combined effect of all of them: toggles done an even number of
+/// times cancel out, so the order doesn't matter.
+pub fn xor_fold(items: impl IntoIterator<Item = u64>) -> u64 {
+    items.into_iter().fold(0, |acc, item| acc ^ item)
+}
+
+/// The parity of the number of set bits of `n`.
+#[must_use]
+pub const fn parity_of_ones(n: u64) -> Parity {
+    Parity::from_count(n.count_ones() as usize)
+}
+
+pub trait NumberIteratorExt: Sized {
+    fn least_common_multiple(self) -> Self::Item
+    where
+        Self: Iterator,
+        Self::Item: NumberExt,
+    {
+        self.fold(Self::Item::one(), Self::Item::least_common_multiple)
+    }
+
+    /// The parity of the sum of all the items, without actually computing the
+    /// sum (which could overflow).
+    fn parity_sum(self) -> Parity
+    where
+        Self: Iterator,
+        Self::Item: NumberExt,
+    {
+        self.map(NumberExt::parity).fold(Parity::Even, |a, b| a ^ b)
+    }
+
+    /// Yields the sum of the items so far, after each item.
+    fn running_sum(self) -> impl Iterator<Item = Self::Item>
+    where
+        Self: Iterator,
+        Self::Item: Copy + core::ops::Add<Output = Self::Item>,
+    {
+        self.scan(None, |sum, item| {
+            let next = sum.map_or(item, |sum| sum + item);
+            *sum = Some(next);
+            Some(next)
+        })
+    }
+
+    /// Yields the largest item so far, after each item.
+    fn running_max(self) -> impl Iterator<Item = Self::Item>
+    where
+        Self: Iterator,
+        Self::Item: Copy + Ord,
+    {
+        self.scan(None, |max, item| {
+            let next = max.map_or(item, |max: Self::Item| max.max(item));
+            *max = Some(next);
+            Some(next)
+        })
+    }
+}
+
+impl<T> NumberIteratorExt for T where T: Iterator {}
+
+pub trait CountExt: Iterator + Sized {
+    /// Counts how many times each distinct item occurs.
+    fn counts(self) -> std::collections::HashMap<Self::Item, usize>
+    where
+        Self::Item: std::hash::Hash + Eq,
+    {
+        let mut counts = std::collections::HashMap::new();
+        for item in self {
+            *counts.entry(item).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// The most frequent item, or `None` if the iterator is empty. If several
+    /// items are tied, returns the one that occurs first.
+    fn mode(self) -> Option<Self::Item>
+    where
+        Self::Item: std::hash::Hash + Eq,
+    {
+        let mut counts = std::collections::HashMap::new();
+        for (index, item) in self.enumerate() {
+            counts.entry(item).or_insert((0, index)).0 += 1;
+        }
+        counts
+            .into_iter()
+            .max_by_key(|&(_, (count, first))| (count, std::cmp::Reverse(first)))
+            .map(|(item, _)| item)
+    }
+}
+
+impl<T> CountExt for T where T: Iterator {}
+
+pub trait NumberDigitsExt: Copy {
     type MaxDigits;
     /// Write the decimal digits of the number into the provided slice, starting
     /// from the least significant digit.
@@ -346,9 +1127,95 @@ pub trait NumberDigitsExt: Copy {
     /// Returns `Err(BufferTooSmall)` if the number of digits exceeds the size
     /// of the buffer.
     fn digits_in(self, slice: &mut [u8]) -> Result<usize, BufferTooSmall>;
+    /// Same as [`NumberDigitsExt::digits_in`], but in the given base.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(BufferTooSmall)` if the number of digits exceeds the size
+    /// of the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not in `2..=36`.
+    fn digits_in_radix(self, radix: u32, slice: &mut [u8]) -> Result<usize, BufferTooSmall>;
     /// Returns the decimal digits of the number as a vector, starting from the
     /// least significant digit.
     fn digits(self) -> Vec<u8>;
+    /// Returns the digits of the number in the given base as a vector,
+    /// starting from the least significant digit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not in `2..=36`.
+    fn digits_radix(self, radix: u32) -> Vec<u8>;
+    /// Builds a number from its digits in the given base, starting from the
+    /// least significant digit. This is the inverse of
+    /// [`NumberDigitsExt::digits_radix`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(DigitError::InvalidDigit)` if a digit is not smaller than
+    /// `radix`, or `Err(DigitError::Overflow)` if the number doesn't fit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not in `2..=36`.
+    fn from_digits_radix(slice: &[u8], radix: u32) -> Result<Self, DigitError>;
+    /// Returns the number with its digits in the given base in reverse order,
+    /// e.g. 1200 becomes 21 in base 10. Returns `None` if the result doesn't
+    /// fit in the type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not in `2..=36`.
+    #[must_use]
+    fn reverse_digits_radix(self, radix: u32) -> Option<Self> {
+        let mut digits = self.digits_radix(radix);
+        digits.reverse();
+        Self::from_digits_radix(&digits, radix).ok()
+    }
+    /// Whether the digits of the number in the given base read the same in
+    /// both directions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not in `2..=36`.
+    #[must_use]
+    fn is_palindrome_radix(self, radix: u32) -> bool {
+        let digits = self.digits_radix(radix);
+        digits.iter().eq(digits.iter().rev())
+    }
+    /// Repeatedly sums the decimal digits until a single digit is left.
+    /// Returns that digit (the digital root) and how many sums it took.
+    fn digital_root_steps(self) -> (u8, usize);
+    /// The length of the shortest block of decimal digits that, repeated,
+    /// gives the whole number, e.g. 2 for 1212. Numbers that aren't made of
+    /// a repeated block have the number of digits as their period.
+    fn smallest_period(self) -> usize;
+    /// Whether the decimal digits are a block repeated at least twice, like
+    /// 1212 or 777.
+    fn is_repeated_block(self) -> bool;
+    /// Whether the number has an even number of decimal digits, and its two
+    /// halves are the same, like 1212 (but not 1221).
+    fn is_mirror_halves(self) -> bool;
+    /// Whether the decimal digits read the same in both directions, like 1221
+    /// or 121 (but not 1212).
+    fn is_palindrome(self) -> bool;
+}
+
+/// The smallest `period` that divides the length of `digits`, such that
+/// `digits` is made of copies of its first `period` items.
+fn digits_period(digits: &[u8]) -> usize {
+    (1..digits.len())
+        .filter(|&period| digits.len().is_multiple_of(period))
+        .find(|&period| digits[period..].iter().zip(digits).all(|(a, b)| a == b))
+        .unwrap_or(digits.len())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DigitError {
+    InvalidDigit(u8),
+    Overflow,
 }
 
 pub struct MaxDigits<T>(std::marker::PhantomData<T>);
@@ -406,6 +1273,98 @@ macro_rules! impl_number_digits_ext_for_num_type {
                     digits.truncate(size);
                     digits
                 }
+
+                fn digits_in_radix(
+                    self,
+                    radix: u32,
+                    slice: &mut [u8],
+                ) -> Result<usize, BufferTooSmall> {
+                    assert!((2..=36).contains(&radix), "radix must be in 2..=36");
+                    #[allow(clippy::cast_possible_truncation)]
+                    let radix = radix as $x;
+                    let mut num = self;
+                    let mut index = 0;
+                    loop {
+                        let digit = slice.get_mut(index).ok_or(BufferTooSmall)?;
+                        #[allow(clippy::cast_possible_truncation)]
+                        {
+                            *digit = (num % radix) as u8;
+                        }
+                        num /= radix;
+                        index += 1;
+                        if num == 0 {
+                            return Ok(index);
+                        }
+                    }
+                }
+
+                fn digits_radix(self, radix: u32) -> Vec<u8> {
+                    // base 2 needs the most digits, one per bit
+                    let mut digits = vec![0; <$x>::BITS as usize];
+                    let size = self.digits_in_radix(radix, &mut digits).unwrap();
+                    digits.truncate(size);
+                    digits
+                }
+
+                fn from_digits_radix(slice: &[u8], radix: u32) -> Result<Self, DigitError> {
+                    assert!((2..=36).contains(&radix), "radix must be in 2..=36");
+                    let mut num: $x = 0;
+                    for &digit in slice.iter().rev() {
+                        if u32::from(digit) >= radix {
+                            return Err(DigitError::InvalidDigit(digit));
+                        }
+                        #[allow(clippy::cast_possible_truncation)]
+                        {
+                            num = num
+                                .checked_mul(radix as $x)
+                                .and_then(|n| n.checked_add(digit as $x))
+                                .ok_or(DigitError::Overflow)?;
+                        }
+                    }
+                    Ok(num)
+                }
+
+                fn digital_root_steps(self) -> (u8, usize) {
+                    let mut num = self;
+                    let mut steps = 0;
+                    while num >= 10 {
+                        let mut sum = 0;
+                        while num > 0 {
+                            sum += num % 10;
+                            num /= 10;
+                        }
+                        num = sum;
+                        steps += 1;
+                    }
+                    #[allow(clippy::cast_possible_truncation)]
+                    (num as u8, steps)
+                }
+
+                fn smallest_period(self) -> usize {
+                    let mut digits = Self::MaxDigits::array();
+                    let len = self.digits_in(&mut digits).unwrap();
+                    digits_period(&digits[..len])
+                }
+
+                fn is_repeated_block(self) -> bool {
+                    let mut digits = Self::MaxDigits::array();
+                    let len = self.digits_in(&mut digits).unwrap();
+                    digits_period(&digits[..len]) < len
+                }
+
+                fn is_mirror_halves(self) -> bool {
+                    let mut digits = Self::MaxDigits::array();
+                    let len = self.digits_in(&mut digits).unwrap();
+                    let (low, high) = digits[..len].split_at(len / 2);
+                    len.is_multiple_of(2) && low == high
+                }
+
+                fn is_palindrome(self) -> bool {
+                    let mut digits = Self::MaxDigits::array();
+                    let len = self.digits_in(&mut digits).unwrap();
+                    let digits = &digits[..len];
+                    digits.iter().eq(digits.iter().rev())
+                }
             }
         )+
     };
@@ -431,6 +1390,33 @@ mod tests {
         assert_eq!(iter.next(), None);
     }
 
+    #[test]
+    fn pairs_iterator_len() {
+        let mut iter = PairsIterator::new(&[1, 2, 3, 4]);
+        assert_eq!(iter.len(), 6);
+        assert_eq!(iter.size_hint(), (6, Some(6)));
+        iter.next();
+        iter.next();
+        assert_eq!(iter.len(), 4);
+        iter.next();
+        iter.next();
+        assert_eq!(iter.len(), 2);
+        for n in 0..7 {
+            let slice = vec![0; n];
+            let mut iter = slice.pairs();
+            assert_eq!(iter.len(), n * n.saturating_sub(1) / 2);
+            loop {
+                let len = iter.len();
+                if iter.next().is_none() {
+                    assert_eq!(len, 0);
+                    break;
+                }
+                assert_eq!(iter.len(), len - 1);
+            }
+            assert_eq!(iter.len(), 0);
+        }
+    }
+
     #[test]
     fn pairs_iterator_too_small() {
         let mut iter = PairsIterator::new(&[1]);
@@ -445,6 +1431,125 @@ mod tests {
         assert_eq!(iter.next(), None);
     }
 
+    #[test]
+    fn check_sorted_by_key() {
+        let sorted = [1, 2, 2, 5];
+        let reversed = [5, 2, 2, 1];
+        let unsorted = [1, 3, 2, 4, 0];
+        assert_eq!(sorted.check_sorted_by_key(|&x| x), Ok(()));
+        assert_eq!(reversed.check_sorted_by_key(|&x| x), Err(1));
+        assert_eq!(unsorted.check_sorted_by_key(|&x| x), Err(2));
+        assert_eq!(sorted.check_sorted_desc_by_key(|&x| x), Err(1));
+        assert_eq!(reversed.check_sorted_desc_by_key(|&x| x), Ok(()));
+        assert_eq!(unsorted.check_sorted_desc_by_key(|&x| x), Err(1));
+        assert_eq!(
+            unsorted.check_sorted_by_key(|&x| std::cmp::Reverse(x)),
+            Err(1)
+        );
+        assert_eq!([0u8; 0].check_sorted_by_key(|&x| x), Ok(()));
+    }
+
+    #[test]
+    fn sliding_max() {
+        assert_eq!([1, 3, 2, 5, 4].sliding_max(2), [3, 3, 5, 5]);
+        assert_eq!([1, 3, 2, 5, 4].sliding_max(3), [3, 5, 5]);
+        assert_eq!([5, 4, 3, 2, 1].sliding_max(2), [5, 4, 3, 2]);
+        assert_eq!([1, 3, 2, 5, 4].sliding_max(1), [1, 3, 2, 5, 4]);
+        assert_eq!([1, 3, 2, 5, 4].sliding_max(5), [5]);
+        assert!([1, 3, 2].sliding_max(4).is_empty());
+        assert!([1, 3, 2].sliding_max(0).is_empty());
+    }
+
+    #[test]
+    fn arg_max() {
+        assert_eq!([3, 1, 3].arg_max_first(), Some(0));
+        assert_eq!([3, 1, 3].arg_max_last(), Some(2));
+        assert_eq!([1, 5, 2].arg_max_first(), Some(1));
+        assert_eq!([1, 5, 2].arg_max_last(), Some(1));
+        assert_eq!([0u8; 0].arg_max_first(), None);
+        assert_eq!([0u8; 0].arg_max_last(), None);
+    }
+
+    #[test]
+    fn permutations() {
+        let mut permutations = [1, 2, 3].permutations().collect::<Vec<_>>();
+        assert_eq!(permutations.len(), 6);
+        permutations.sort_unstable();
+        assert_eq!(
+            permutations,
+            [
+                [&1, &2, &3],
+                [&1, &3, &2],
+                [&2, &1, &3],
+                [&2, &3, &1],
+                [&3, &1, &2],
+                [&3, &2, &1],
+            ]
+        );
+        assert_eq!([0; 5].permutations().count(), 120);
+        assert_eq!([7].permutations().collect::<Vec<_>>(), [[&7]]);
+        let empty: [u8; 0] = [];
+        assert_eq!(
+            empty.permutations().collect::<Vec<_>>(),
+            [Vec::<&u8>::new()]
+        );
+    }
+
+    #[test]
+    fn ordered_pairs() {
+        let pairs = [1, 2, 3].ordered_pairs().collect::<Vec<_>>();
+        assert_eq!(
+            pairs,
+            [(&1, &2), (&1, &3), (&2, &1), (&2, &3), (&3, &1), (&3, &2)]
+        );
+        for n in 0..6usize {
+            let slice = (0..n).collect::<Vec<_>>();
+            assert_eq!(slice.ordered_pairs().count(), n * n.saturating_sub(1));
+        }
+        // equal elements at different indices still make pairs
+        assert_eq!([7, 7].ordered_pairs().count(), 2);
+    }
+
+    #[test]
+    fn index_pairs() {
+        let slice = [10, 20, 30, 40, 50];
+        let mut expected = Vec::new();
+        for i in 0..slice.len() {
+            for j in i + 1..slice.len() {
+                expected.push((i, j));
+            }
+        }
+        assert_eq!(slice.index_pairs().collect::<Vec<_>>(), expected);
+        assert_eq!(slice.index_pairs().count(), 5 * 4 / 2);
+        let pairs = slice.pairs().collect::<Vec<_>>();
+        let indexed = slice
+            .index_pairs()
+            .map(|(i, j)| (&slice[i], &slice[j]))
+            .collect::<Vec<_>>();
+        assert_eq!(pairs, indexed);
+        assert_eq!([1].index_pairs().count(), 0);
+    }
+
+    #[test]
+    fn windows_of() {
+        let slice = [1, 2, 3, 4];
+        let windows = slice.windows_of(3).collect::<Vec<_>>();
+        assert_eq!(windows, [&[1, 2, 3][..], &[2, 3, 4][..]]);
+        assert_eq!(slice.windows_of(4).count(), 1);
+        assert_eq!(slice.windows_of(5).count(), 0);
+        assert_eq!(slice.windows_of(0).count(), 0);
+    }
+
+    #[test]
+    fn chunks_exact_of() {
+        let slice = [1, 2, 3, 4, 5];
+        let chunks = slice.chunks_exact_of(2).collect::<Vec<_>>();
+        assert_eq!(chunks, [&[1, 2][..], &[3, 4][..]]);
+        assert_eq!(slice.chunks_exact_of(5).count(), 1);
+        assert_eq!(slice.chunks_exact_of(6).count(), 0);
+        assert_eq!(slice.chunks_exact_of(0).count(), 0);
+    }
+
     #[test]
     fn ascii_lines() {
         let mut iter = LinesIterator::new(b"abc\ndef\nghi\n");
@@ -506,6 +1611,101 @@ mod tests {
         assert!(matches!(foo.as_slice().parse::<Foo>(), Ok(Foo)));
     }
 
+    #[test]
+    fn ascii_parse_pair() {
+        assert_eq!(b"3-5".as_slice().parse_pair(b'-'), Ok((3u64, 5u64)));
+        assert_eq!(b"12=-4".as_slice().parse_pair(b'='), Ok((12u8, -4i32)));
+        assert_eq!(
+            b"35".as_slice().parse_pair::<u64, u64>(b'-'),
+            Err(ParsePairError::MissingSeparator)
+        );
+        assert!(matches!(
+            b"3-x".as_slice().parse_pair::<u64, u64>(b'-'),
+            Err(ParsePairError::Second(_))
+        ));
+    }
+
+    #[test]
+    fn parse_signed_token() {
+        use super::parse_signed_token;
+        assert_eq!(parse_signed_token("R14", 'R', 'L'), Some(14));
+        assert_eq!(parse_signed_token("L68", 'R', 'L'), Some(-68));
+        assert_eq!(parse_signed_token("U0", 'D', 'U'), Some(0));
+        assert_eq!(parse_signed_token("X5", 'R', 'L'), None);
+        assert_eq!(parse_signed_token("r5", 'R', 'L'), None);
+        assert_eq!(parse_signed_token("R", 'R', 'L'), None);
+        assert_eq!(parse_signed_token("R1x", 'R', 'L'), None);
+        assert_eq!(parse_signed_token("L-3", 'R', 'L'), None);
+        assert_eq!(parse_signed_token("", 'R', 'L'), None);
+        assert_eq!(parse_signed_token("R+5", 'R', 'L'), None);
+        assert_eq!(parse_signed_token("R2147483648", 'R', 'L'), Some(1 << 31));
+        assert_eq!(
+            parse_signed_token("L9223372036854775807", 'R', 'L'),
+            Some(-i64::MAX)
+        );
+        assert_eq!(parse_signed_token("R9223372036854775808", 'R', 'L'), None);
+    }
+
+    #[test]
+    fn sections() {
+        let input = b"3-5\n10-14\n\n1\n5\n8\n".as_slice();
+        assert_eq!(
+            input.sections().collect::<Vec<_>>(),
+            [b"3-5\n10-14".as_slice(), b"1\n5\n8"]
+        );
+        let input = b"\na\n\n\n\nb\nc\n\n".as_slice();
+        assert_eq!(
+            input.sections().collect::<Vec<_>>(),
+            [b"a".as_slice(), b"b\nc"]
+        );
+        assert_eq!(b"".as_slice().sections().count(), 0);
+        assert_eq!(b"\n\n".as_slice().sections().count(), 0);
+    }
+
+    #[test]
+    fn crlf() {
+        let input = b"3-5\r\n10-14\r\n\r\n1\r\n5\r\n".as_slice();
+        let sections: Vec<_> = input.sections().collect();
+        assert_eq!(sections, [b"3-5\r\n10-14".as_slice(), b"1\r\n5"]);
+        assert_eq!(
+            sections[0].ascii_lines().collect::<Vec<_>>(),
+            [b"3-5".as_slice(), b"10-14"]
+        );
+        assert_eq!(b"\r\n\r\n".as_slice().sections().count(), 0);
+        assert_eq!(
+            b"a\r\nb\nc".as_slice().ascii_lines().collect::<Vec<_>>(),
+            [b"a".as_slice(), b"b", b"c"]
+        );
+    }
+
+    #[test]
+    fn parse_numbers() {
+        let numbers = b"57,618,57".as_slice().parse_numbers::<u32>();
+        assert_eq!(
+            numbers.collect::<Result<Vec<_>, _>>(),
+            Ok(vec![57, 618, 57])
+        );
+        let input = b"p=-3,10 v=4,-12".as_slice();
+        let numbers = input.parse_numbers::<i32>();
+        assert_eq!(
+            numbers.collect::<Result<Vec<_>, _>>(),
+            Ok(vec![3, 10, 4, 12])
+        );
+        let numbers = input.parse_signed_numbers::<i32>();
+        assert_eq!(
+            numbers.collect::<Result<Vec<_>, _>>(),
+            Ok(vec![-3, 10, 4, -12])
+        );
+        // a dash between numbers is taken as a sign
+        let numbers = b"3-5".as_slice().parse_signed_numbers::<i8>();
+        assert_eq!(numbers.collect::<Result<Vec<_>, _>>(), Ok(vec![3, -5]));
+        assert_eq!(b"--".as_slice().parse_numbers::<u8>().count(), 0);
+        let mut numbers = b"12 300".as_slice().parse_numbers::<u8>();
+        assert_eq!(numbers.next(), Some(Ok(12)));
+        assert!(numbers.next().unwrap().is_err());
+        assert!(numbers.next().is_none());
+    }
+
     #[test]
     fn ascii_grid() {
         let grid = b"abc\ndef\nghi\njkl".as_slice().grid_like::<u8>().unwrap();
@@ -514,6 +1714,441 @@ mod tests {
         assert_eq!(grid.cells, b"abcdefghijkl".to_vec(),);
     }
 
+    #[test]
+    fn char_grid() {
+        let input = "aé\ncd";
+        let grid = input.char_grid::<char>().unwrap();
+        assert_eq!(grid.width, 2);
+        assert_eq!(grid.height, 2);
+        assert_eq!(grid.cells, ['a', 'é', 'c', 'd']);
+        let grid: Vec<Vec<char>> = grid.into_grid();
+        assert_eq!(grid[0][1], 'é');
+        // the byte version counts the two bytes of 'é' as separate columns
+        let bytes = input.as_bytes().grid_like::<u8>().unwrap();
+        assert_eq!(bytes.width, 3);
+        assert_eq!(bytes.cells.len(), 5);
+        for input in ["ab\r\ncd\r\n", "ab\r\ncd\r\n\r\n"] {
+            let grid = input.char_grid::<char>().unwrap();
+            assert_eq!((grid.width, grid.height), (2, 2));
+            assert_eq!(grid.cells, ['a', 'b', 'c', 'd']);
+        }
+    }
+
+    #[test]
+    fn ascii_grid_trailing_blank_lines() {
+        for input in [
+            b"ab\ncd".as_slice(),
+            b"ab\ncd\n",
+            b"ab\ncd\n\n",
+            b"ab\ncd\n\n\n",
+        ] {
+            let grid = input.grid_like::<u8>().unwrap();
+            assert_eq!((grid.width, grid.height), (2, 2));
+            assert_eq!(grid.cells, b"abcd");
+        }
+        let grid = b"\n\n".as_slice().grid_like::<u8>().unwrap();
+        assert_eq!((grid.width, grid.height), (0, 0));
+        let grid = "é\n\n".char_grid::<char>().unwrap();
+        assert_eq!((grid.width, grid.height), (1, 1));
+    }
+
+    #[test]
+    fn counts() {
+        let counts = b"abracadabra".iter().counts();
+        assert_eq!(counts.len(), 5);
+        assert_eq!(counts[&b'a'], 5);
+        assert_eq!(counts[&b'b'], 2);
+        assert_eq!(counts[&b'd'], 1);
+        assert!(std::iter::empty::<u8>().counts().is_empty());
+    }
+
+    #[test]
+    fn mode() {
+        assert_eq!([3, 1, 3, 2].into_iter().mode(), Some(3));
+        // 2 and 1 are tied, and 2 comes first
+        assert_eq!([2, 1, 1, 2, 3].into_iter().mode(), Some(2));
+        assert_eq!(std::iter::empty::<u8>().mode(), None);
+    }
+
+    #[test]
+    fn running_sum() {
+        assert_eq!(
+            [1, 2, 3].into_iter().running_sum().collect::<Vec<_>>(),
+            [1, 3, 6]
+        );
+        assert_eq!(
+            [5i32, -7, 2].into_iter().running_sum().collect::<Vec<_>>(),
+            [5, -2, 0]
+        );
+        assert_eq!(std::iter::empty::<u8>().running_sum().count(), 0);
+    }
+
+    #[test]
+    fn running_max() {
+        assert_eq!(
+            [3, 1, 4, 1, 5, 9, 2]
+                .into_iter()
+                .running_max()
+                .collect::<Vec<_>>(),
+            [3, 3, 4, 4, 5, 9, 9]
+        );
+        assert_eq!(
+            [-2i8, -5].into_iter().running_max().collect::<Vec<_>>(),
+            [-2, -2]
+        );
+        assert_eq!(std::iter::empty::<u8>().running_max().count(), 0);
+    }
+
+    #[test]
+    fn parity_sum() {
+        assert_eq!([1u32, 2, 4].into_iter().parity_sum(), Parity::Odd);
+        assert_eq!([1u32, 3, 4].into_iter().parity_sum(), Parity::Even);
+        assert_eq!(
+            [u64::MAX, u64::MAX, 1].into_iter().parity_sum(),
+            Parity::Odd
+        );
+        assert_eq!(std::iter::empty::<u8>().parity_sum(), Parity::Even);
+    }
+
+    #[test]
+    fn parity_from_count() {
+        assert_eq!(Parity::from_count(0), Parity::Even);
+        assert_eq!(Parity::from_count(3), Parity::Odd);
+        assert_eq!(Parity::from_count(3) ^ Parity::from_count(5), Parity::Even);
+        assert_eq!(Parity::from_count(2) ^ Parity::from_count(5), Parity::Odd);
+    }
+
+    #[test]
+    fn grid_like_find_cells() {
+        // from day 7
+        let grid = b".......S.......\n...............\n.......^......."
+            .as_slice()
+            .grid_like::<u8>()
+            .unwrap();
+        assert_eq!(grid.find_cell(|&c| c == b'S'), Some((7, 0)));
+        assert_eq!(grid.find_cells(|&c| c == b'S'), [(7, 0)]);
+        assert_eq!(grid.find_cell(|&c| c == b'x'), None);
+        // from day 4
+        let grid = b"..@@.\n@@@.@\n.....".as_slice().grid_like::<u8>().unwrap();
+        assert_eq!(
+            grid.find_cells(|&c| c == b'@'),
+            [(2, 0), (3, 0), (0, 1), (1, 1), (2, 1), (4, 1)]
+        );
+        assert!(grid.find_cells(|&c| c == b'x').is_empty());
+    }
+
+    #[test]
+    fn grid_like_padded() {
+        let grid = b"ab\ncd"
+            .as_slice()
+            .grid_like::<u8>()
+            .unwrap()
+            .padded(1, b'.');
+        assert_eq!(grid.width, 4);
+        assert_eq!(grid.height, 4);
+        assert_eq!(grid.cells, b".....ab..cd.....".to_vec());
+        let grid = b"ab\ncd"
+            .as_slice()
+            .grid_like::<u8>()
+            .unwrap()
+            .padded(0, b'.');
+        assert_eq!(grid.cells, b"abcd".to_vec());
+    }
+
+    grid_cell_enum! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        enum Cell {
+            Empty => b'.',
+            Splitter => b'^',
+            Start => b'S',
+        }
+    }
+
+    #[test]
+    fn grid_cell_enum_byte() {
+        assert_eq!(Cell::Splitter.byte(), b'^');
+        for cell in [Cell::Empty, Cell::Splitter, Cell::Start] {
+            assert_eq!(Cell::try_from(cell.byte()).unwrap(), cell);
+            assert_eq!(cell.to_string().as_bytes(), [cell.byte()]);
+        }
+        assert!(Cell::try_from(b'#').is_err());
+    }
+
+    #[test]
+    fn grid_cell_enum_all() {
+        assert_eq!(Cell::ALL, [Cell::Empty, Cell::Splitter, Cell::Start]);
+        for cell in Cell::ALL {
+            assert_eq!(Cell::ALL.iter().filter(|&&c| c == cell).count(), 1);
+        }
+        assert_eq!(
+            NoisyCell::ALL,
+            [NoisyCell::Wall, NoisyCell::Floor, NoisyCell::Other]
+        );
+    }
+
+    grid_cell_enum! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        enum NoisyCell {
+            Wall => b'#',
+            Floor => b'.',
+            _ => Other => b'?',
+        }
+    }
+
+    #[test]
+    fn grid_cell_enum_default() {
+        assert_eq!(NoisyCell::try_from(b'#').unwrap(), NoisyCell::Wall);
+        assert_eq!(NoisyCell::try_from(b'x').unwrap(), NoisyCell::Other);
+        assert_eq!(NoisyCell::try_from(b'?').unwrap(), NoisyCell::Other);
+        assert_eq!(NoisyCell::Other.to_string(), "?");
+        assert_eq!(NoisyCell::Other.byte(), b'?');
+        let grid = b"#x\n.#".as_slice().grid_like::<NoisyCell>().unwrap();
+        assert_eq!(
+            grid.cells,
+            [
+                NoisyCell::Wall,
+                NoisyCell::Other,
+                NoisyCell::Floor,
+                NoisyCell::Wall
+            ]
+        );
+    }
+
+    #[test]
+    fn memo_recursive() {
+        use std::collections::HashMap;
+
+        fn count<'a>(
+            edges: &HashMap<&'a str, Vec<&'a str>>,
+            memo: &mut Memo<&'a str, u64>,
+            node: &'a str,
+        ) -> u64 {
+            memo.get_or_compute(node, |memo, &node| {
+                if node == "out" {
+                    return 1;
+                }
+                edges[node]
+                    .iter()
+                    .map(|child| count(edges, memo, child))
+                    .sum()
+            })
+        }
+
+        let edges = HashMap::from([
+            ("aaa", vec!["you", "hhh"]),
+            ("you", vec!["bbb", "ccc"]),
+            ("bbb", vec!["ddd", "eee"]),
+            ("ccc", vec!["ddd", "eee", "fff"]),
+            ("ddd", vec!["ggg"]),
+            ("eee", vec!["out"]),
+            ("fff", vec!["out"]),
+            ("ggg", vec!["out"]),
+            ("hhh", vec!["ccc", "fff", "iii"]),
+            ("iii", vec!["out"]),
+        ]);
+        let mut memo = Memo::new();
+        assert_eq!(count(&edges, &mut memo, "you"), 5);
+        assert_eq!(memo.get(&"ccc"), Some(&3));
+        assert_eq!(memo.get(&"aaa"), None);
+    }
+
+    #[test]
+    fn dense_memo() {
+        fn fibonacci(memo: &mut DenseMemo<u8, u64>, n: u8) -> u64 {
+            memo.get_or_compute(n, |memo, n| {
+                if n < 2 {
+                    n.into()
+                } else {
+                    fibonacci(memo, n - 1) + fibonacci(memo, n - 2)
+                }
+            })
+        }
+
+        let mut memo = DenseMemo::new();
+        assert_eq!(fibonacci(&mut memo, 90), 2_880_067_194_370_816_120);
+        assert_eq!(memo.get(10), Some(&55));
+        assert_eq!(memo.get(91), None);
+    }
+
+    #[test]
+    fn abs_difference() {
+        for (a, b) in [(3u32, 10), (10, 3), (0, u32::MAX), (7, 7)] {
+            assert_eq!(a.abs_difference(b), a.abs_diff(b));
+        }
+        assert_eq!((-5i64).abs_difference(3), 8);
+        assert_eq!(3i64.abs_difference(-5), 8);
+        assert_eq!((-5i64).abs_difference(-3), 2);
+        assert_eq!(0usize.abs_difference(0), 0);
+    }
+
+    #[test]
+    fn halves() {
+        assert_eq!(8u32.halves(), (4, 4));
+        assert_eq!(7u32.halves(), (4, 3));
+        assert_eq!(1u8.halves(), (1, 0));
+        assert_eq!(0u8.halves(), (0, 0));
+        assert_eq!((-7i32).halves(), (-4, -3));
+        assert_eq!((-8i32).halves(), (-4, -4));
+        let (a, b) = u64::MAX.halves();
+        assert_eq!((a, b), (1 << 63, (1 << 63) - 1));
+    }
+
+    #[test]
+    fn checked_lcm() {
+        assert_eq!(4u32.checked_lcm(6), Some(12));
+        assert_eq!(4u32.checked_gcd(6), Some(2));
+        assert_eq!(0u32.checked_lcm(6), Some(0));
+        // the product of the two doesn't fit, but their lcm does
+        let (a, b) = (3u64 << 40, 5u64 << 40);
+        assert_eq!(a.checked_mul(b), None);
+        assert_eq!(a.checked_lcm(b), Some(15 << 40));
+        assert_eq!(a.least_common_multiple(b), 15 << 40);
+        assert_eq!(u64::MAX.checked_lcm(u64::MAX - 1), None);
+        assert_eq!(u128::MAX.checked_lcm(u128::MAX - 1), None);
+        assert_eq!((-4i32).checked_lcm(6), None);
+    }
+
+    const _: u32 = <u32 as ConstNumber>::ONE;
+    const _: i8 = <i8 as ConstNumber>::ZERO;
+
+    #[test]
+    fn const_number() {
+        const fn one<T: ConstNumber>() -> T {
+            T::ONE
+        }
+        const TWO: u64 = one::<u64>() + one::<u64>();
+        assert_eq!(TWO, 2);
+        assert_eq!(<i64 as ConstNumber>::ZERO, i64::zero());
+        assert_eq!(<u8 as ConstNumber>::ONE, u8::one());
+    }
+
+    #[test]
+    fn set_bit_indices() {
+        assert_eq!(set_bits(0b10110).collect::<Vec<_>>(), [1, 2, 4]);
+        assert_eq!(set_bits(0).count(), 0);
+        assert_eq!(set_bits(1 << 63).collect::<Vec<_>>(), [63]);
+        assert_eq!(set_bits(u64::MAX).count(), 64);
+    }
+
+    #[test]
+    fn xor_folding() {
+        assert_eq!(xor_fold([0b0011, 0b0110, 0b1100]), 0b1001);
+        // pressing the same button twice does nothing
+        assert_eq!(xor_fold([0b101, 0b011, 0b101]), 0b011);
+        assert_eq!(xor_fold([]), 0);
+    }
+
+    #[test]
+    fn parity_of_set_bits() {
+        assert_eq!(parity_of_ones(u64::MAX), Parity::Even);
+        assert_eq!(parity_of_ones(u64::MAX >> 1), Parity::Odd);
+        assert_eq!(parity_of_ones(0), Parity::Even);
+        assert_eq!(parity_of_ones(0b10110), Parity::Odd);
+        let items = [0b0011, 0b0110, 0b1100];
+        assert_eq!(
+            parity_of_ones(xor_fold(items)),
+            items
+                .into_iter()
+                .map(parity_of_ones)
+                .fold(Parity::Even, |a, b| a ^ b)
+        );
+    }
+
+    #[test]
+    fn choose() {
+        assert_eq!(u64::choose(5, 2), Some(10));
+        assert_eq!(u64::choose(4, 0), Some(1));
+        assert_eq!(u64::choose(4, 4), Some(1));
+        assert_eq!(u64::choose(3, 5), Some(0));
+        assert_eq!(u64::choose(62, 31), Some(465_428_353_255_261_088));
+        assert_eq!(u8::choose(10, 5), Some(252));
+        assert_eq!(u8::choose(11, 5), None);
+        assert_eq!(u64::choose(100, 50), None);
+        assert_eq!(i32::choose(-3, 1), None);
+    }
+
+    #[test]
+    fn prefix_sum() {
+        let mut state = 0x2025u64;
+        let mut random = |max: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % max
+        };
+        let values = (0..100).map(|_| random(1000)).collect::<Vec<_>>();
+        let sums = PrefixSum::new(&values);
+        for _ in 0..100 {
+            let start = random(100) as usize;
+            let end = start + random(100 - start as u64) as usize;
+            let naive = values[start..=end].iter().sum::<u64>();
+            assert_eq!(sums.range_sum(start..=end), naive);
+        }
+        assert_eq!(sums.range_sum(0..=99), values.iter().sum::<u64>());
+    }
+
+    #[test]
+    fn prefix_product() {
+        let values = [3u64, 1, 4, 1, 5, 9, 2, 6];
+        let products = PrefixSum::with_fold(&values, 1, |a, b| a * b, |a, b| a / b);
+        assert_eq!(products.range_sum(2..=5), 4 * 5 * 9);
+        assert_eq!(products.range_sum(7..=7), 6);
+        assert_eq!(products.range_sum(0..=7), 6480);
+    }
+
+    #[test]
+    fn sqrt() {
+        assert_eq!(99u64.sqrt_ceil(), 10);
+        assert_eq!(100u64.sqrt_ceil(), 10);
+        assert_eq!(101u64.sqrt_ceil(), 11);
+        assert_eq!(99u64.integer_sqrt(), 9);
+        assert_eq!(100u64.integer_sqrt(), 10);
+        assert_eq!(0u8.sqrt_ceil(), 0);
+        assert_eq!(1i32.sqrt_ceil(), 1);
+        assert_eq!(u64::MAX.integer_sqrt(), u64::from(u32::MAX));
+        assert_eq!(u64::MAX.sqrt_ceil(), 1 << 32);
+    }
+
+    #[test]
+    fn mod_counter() {
+        let dial = ModCounter::<100>::new(50);
+        assert_eq!(dial.rotate(-68).value(), 82);
+        assert_eq!(dial.rotate(150).value(), 0);
+        assert_eq!(ModCounter::<100>::new(250).value(), 50);
+        let counter = ModCounter::<7>::new(3);
+        assert_eq!(counter.rotate(4).value(), 0);
+        assert_eq!(counter.rotate(-4).value(), 6);
+        assert_eq!(counter.rotate(0), counter);
+    }
+
+    #[test]
+    fn mod_counter_wraps() {
+        fn check<const CAP: u16>(start: u16, amount: i32, value: u16, wraps: u32) {
+            let (counter, count) = ModCounter::<CAP>::new(start).rotate_counting_wraps(amount);
+            assert_eq!(
+                (counter.value(), count),
+                (value, wraps),
+                "{start} {amount:+}"
+            );
+        }
+        // full turns
+        check::<100>(50, 1000, 50, 10);
+        check::<100>(50, -1000, 50, 10);
+        // a partial pass
+        check::<100>(50, 60, 10, 1);
+        check::<100>(50, -60, 90, 1);
+        check::<100>(50, 25, 75, 0);
+        // stopping at zero counts, leaving it doesn't
+        check::<100>(50, -50, 0, 1);
+        check::<100>(0, -5, 95, 0);
+        check::<100>(0, 100, 0, 1);
+        // other sizes
+        check::<12>(6, 30, 0, 3);
+        check::<12>(6, -13, 5, 1);
+        check::<12>(6, 5, 11, 0);
+        check::<1>(0, -3, 0, 3);
+    }
+
     #[test]
     fn max_digits() {
         let x = u64::MAX;
@@ -557,6 +2192,158 @@ mod tests {
         );
     }
 
+    #[test]
+    fn annotate_and() {
+        let a = 1.annotate((0, 1)).and("a");
+        let b = 2.annotate((2, 3)).and("b");
+        let c = 1.annotate((4, 5)).and("c");
+        assert_eq!(a.annotation, ((0, 1), "a"));
+        assert!(a < b);
+        assert_eq!(a.cmp(&c), core::cmp::Ordering::Equal);
+        let mut sorted = [b, c, a];
+        sorted.sort();
+        assert_eq!(sorted[2].annotation, ((2, 3), "b"));
+    }
+
+    #[test]
+    fn annotate_map_annotation() {
+        let a = 5.annotate("five").map_annotation(str::len);
+        let b = 5.annotate(100);
+        assert_eq!(a.annotation, 4);
+        assert_eq!(a.cmp(&b), core::cmp::Ordering::Equal);
+        assert_eq!(a, b);
+        assert_eq!(*a.value(), 5);
+        assert_eq!(b.into_value(), 5);
+    }
+
+    #[test]
+    fn annotate_ref_heap() {
+        let paths = [vec![1, 2, 3], vec![4], vec![5, 6]];
+        let mut heap = std::collections::BinaryHeap::new();
+        for (i, path) in paths.iter().enumerate() {
+            heap.push(path.annotate_ref(i));
+        }
+        assert_eq!(heap.pop().map(|a| a.annotation), Some(2));
+        assert_eq!(heap.pop().map(|a| a.annotation), Some(1));
+        assert_eq!(heap.pop().map(|a| a.annotation), Some(0));
+        assert!(heap.pop().is_none());
+    }
+
+    #[test]
+    fn drain_sorted_annotations() {
+        let mut heap = std::collections::BinaryHeap::new();
+        for (name, distance) in [("b", 25u64), ("a", 9), ("d", 100), ("c", 25)] {
+            heap.push(distance.annotate(name));
+        }
+        let drained = super::drain_sorted_annotations(heap);
+        assert_eq!(drained.len(), 4);
+        assert_eq!(drained[0], "a");
+        // ties can come out in either order
+        assert!(drained[1..3] == ["b", "c"] || drained[1..3] == ["c", "b"]);
+        assert_eq!(drained[3], "d");
+    }
+
+    #[test]
+    fn digits_in_radix() {
+        let mut buf = [0u8; 8];
+        assert_eq!(255u8.digits_in_radix(16, &mut buf), Ok(2));
+        assert_eq!(buf[..2], [15, 15]);
+        assert_eq!(255u8.digits_in_radix(2, &mut buf), Ok(8));
+        assert_eq!(buf, [1; 8]);
+        assert_eq!(0u32.digits_in_radix(36, &mut buf), Ok(1));
+        assert_eq!(buf[0], 0);
+        assert_eq!(35u32.digits_in_radix(36, &mut buf), Ok(1));
+        assert_eq!(buf[0], 35);
+        assert_eq!(256u16.digits_in_radix(2, &mut buf), Err(BufferTooSmall));
+        assert_eq!(0u8.digits_in_radix(2, &mut []), Err(BufferTooSmall));
+    }
+
+    #[test]
+    fn digits_radix() {
+        assert_eq!(10u32.digits_radix(2), [0, 1, 0, 1]);
+        assert_eq!(0u32.digits_radix(2), [0]);
+        assert_eq!(255u8.digits_radix(16), [15, 15]);
+        assert_eq!(u64::MAX.digits_radix(10), u64::MAX.digits());
+    }
+
+    #[test]
+    fn smallest_period() {
+        assert_eq!(1212u32.smallest_period(), 2);
+        assert_eq!(123123u32.smallest_period(), 3);
+        assert_eq!(1234u32.smallest_period(), 4);
+        assert_eq!(1111u32.smallest_period(), 1);
+        assert_eq!(121u32.smallest_period(), 3);
+        assert_eq!(0u8.smallest_period(), 1);
+        assert_eq!(u64::MAX.smallest_period(), 20);
+        assert!(1212u32.is_repeated_block());
+        assert!(777u16.is_repeated_block());
+        assert!(!1234u32.is_repeated_block());
+        assert!(!121u32.is_repeated_block());
+        assert!(!7u8.is_repeated_block());
+    }
+
+    #[test]
+    fn mirror_halves_and_palindrome() {
+        assert!(1212u32.is_mirror_halves());
+        assert!(!1212u32.is_palindrome());
+        assert!(!1221u32.is_mirror_halves());
+        assert!(1221u32.is_palindrome());
+        assert!(!1234u32.is_mirror_halves());
+        assert!(!1234u32.is_palindrome());
+        // odd lengths can't be split in two halves
+        assert!(!121u32.is_mirror_halves());
+        assert!(121u32.is_palindrome());
+        assert!(!7u8.is_mirror_halves());
+        assert!(7u8.is_palindrome());
+        assert!(11u8.is_mirror_halves());
+        assert!(11u8.is_palindrome());
+    }
+
+    #[test]
+    fn reverse_digits_radix() {
+        assert_eq!(0b1100u8.reverse_digits_radix(2), Some(0b0011));
+        assert_eq!(1200u32.reverse_digits_radix(10), Some(21));
+        assert_eq!(0u32.reverse_digits_radix(10), Some(0));
+        assert_eq!(0xabcu16.reverse_digits_radix(16), Some(0xcba));
+        assert_eq!(1_000_000_009u32.reverse_digits_radix(10), None);
+        assert!(585u32.is_palindrome_radix(10));
+        // 585 is 0b1001001001
+        assert!(585u32.is_palindrome_radix(2));
+        assert!(!1200u32.is_palindrome_radix(10));
+        assert!(0u8.is_palindrome_radix(2));
+        assert!(!6u8.is_palindrome_radix(2));
+    }
+
+    #[test]
+    fn from_digits_radix() {
+        assert_eq!(u32::from_digits_radix(&[0, 1, 0, 1], 2), Ok(10));
+        assert_eq!(u32::from_digits_radix(&[], 2), Ok(0));
+        assert_eq!(u8::from_digits_radix(&[15, 15], 16), Ok(255));
+        assert_eq!(
+            u32::from_digits_radix(&[0, 2], 2),
+            Err(DigitError::InvalidDigit(2))
+        );
+        assert_eq!(
+            u8::from_digits_radix(&[6, 5, 2], 10),
+            Err(DigitError::Overflow)
+        );
+        for radix in [2, 3, 10, 16, 36] {
+            for n in [0u64, 1, 35, 1234567, u64::MAX] {
+                assert_eq!(u64::from_digits_radix(&n.digits_radix(radix), radix), Ok(n));
+            }
+        }
+    }
+
+    #[test]
+    fn digital_root_steps() {
+        assert_eq!(5u32.digital_root_steps(), (5, 0));
+        assert_eq!(0u32.digital_root_steps(), (0, 0));
+        assert_eq!(10u32.digital_root_steps(), (1, 1));
+        assert_eq!(9999u32.digital_root_steps(), (9, 2));
+        assert_eq!(u8::MAX.digital_root_steps(), (3, 2));
+        assert_eq!(u64::MAX.digital_root_steps(), (6, 3));
+    }
+
     #[test]
     fn digits_in_small_buf() {
         let mut buf = [0; 2];
@@ -565,6 +2352,200 @@ mod tests {
     }
 }
 
+/// Caches the results of a computation by key. The computation is given
+/// access to the memo itself, so it can be recursive.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: std::collections::HashMap<K, V>,
+}
+
+impl<K, V> Memo<K, V>
+where
+    K: std::hash::Hash + Eq,
+    V: Clone,
+{
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            cache: std::collections::HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// Returns the cached value for `key`, computing it with `f` if it's
+    /// missing.
+    pub fn get_or_compute(&mut self, key: K, f: impl FnOnce(&mut Self, &K) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            return value.clone();
+        }
+        let value = f(self, &key);
+        self.cache.insert(key, value.clone());
+        value
+    }
+}
+
+impl<K, V> Default for Memo<K, V>
+where
+    K: std::hash::Hash + Eq,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Same as [`Memo`], but for keys that can be used as small indices. Values
+/// are stored in a vector that grows as needed.
+#[derive(Debug, Clone)]
+pub struct DenseMemo<K, V> {
+    cache: Vec<Option<V>>,
+    marker: std::marker::PhantomData<K>,
+}
+
+impl<K, V> DenseMemo<K, V>
+where
+    K: Into<usize> + Copy,
+    V: Clone,
+{
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            cache: Vec::new(),
+            marker: std::marker::PhantomData,
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, key: K) -> Option<&V> {
+        self.cache.get(key.into())?.as_ref()
+    }
+
+    /// Returns the cached value for `key`, computing it with `f` if it's
+    /// missing.
+    pub fn get_or_compute(&mut self, key: K, f: impl FnOnce(&mut Self, K) -> V) -> V {
+        if let Some(value) = self.get(key) {
+            return value.clone();
+        }
+        let value = f(self, key);
+        let index = key.into();
+        if index >= self.cache.len() {
+            self.cache.resize(index + 1, None);
+        }
+        self.cache[index] = Some(value.clone());
+        value
+    }
+}
+
+impl<K, V> Default for DenseMemo<K, V>
+where
+    K: Into<usize> + Copy,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Answers range queries over a slice in O(1), after O(n) preprocessing, by
+/// storing the running fold of every prefix.
+#[derive(Debug, Clone)]
+pub struct PrefixSum<T> {
+    prefixes: Vec<T>,
+    uncombine: fn(T, T) -> T,
+}
+
+impl<T: Copy> PrefixSum<T> {
+    /// Prefix sums of `values`.
+    #[must_use]
+    pub fn new(values: &[T]) -> Self
+    where
+        T: Default + std::ops::Add<Output = T> + std::ops::Sub<Output = T>,
+    {
+        Self::with_fold(values, T::default(), |a, b| a + b, |a, b| a - b)
+    }
+
+    /// Prefix folds of `values` with any associative `combine` operation
+    /// that can be undone: `uncombine(combine(a, b), a)` must be `b`. For
+    /// example, products can be undone by division as long as there are no
+    /// zeros.
+    #[must_use]
+    pub fn with_fold(
+        values: &[T],
+        identity: T,
+        combine: fn(T, T) -> T,
+        uncombine: fn(T, T) -> T,
+    ) -> Self {
+        let mut prefixes = Vec::with_capacity(values.len() + 1);
+        prefixes.push(identity);
+        let mut acc = identity;
+        for &value in values {
+            acc = combine(acc, value);
+            prefixes.push(acc);
+        }
+        Self {
+            prefixes,
+            uncombine,
+        }
+    }
+
+    /// The fold of the values with indices in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range ends past the end of the slice.
+    #[must_use]
+    pub fn range_sum(&self, range: std::ops::RangeInclusive<usize>) -> T {
+        let (start, end) = range.into_inner();
+        (self.uncombine)(self.prefixes[end + 1], self.prefixes[start])
+    }
+}
+
+/// A counter that wraps around after `CAP - 1`, like a dial with `CAP`
+/// positions numbered from 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModCounter<const CAP: u16>(u16);
+
+impl<const CAP: u16> ModCounter<CAP> {
+    /// A counter starting at `value`, reduced modulo `CAP`.
+    #[must_use]
+    pub const fn new(value: u16) -> Self {
+        Self(value % CAP)
+    }
+
+    #[must_use]
+    pub const fn value(self) -> u16 {
+        self.0
+    }
+
+    /// Moves the counter up by `amount`, or down if `amount` is negative.
+    #[must_use]
+    pub const fn rotate(self, amount: i32) -> Self {
+        self.rotate_counting_wraps(amount).0
+    }
+
+    /// Same as [`ModCounter::rotate`], but also returns how many times the
+    /// counter stopped at or passed through zero along the way. Starting at
+    /// zero doesn't count.
+    #[must_use]
+    pub const fn rotate_counting_wraps(self, amount: i32) -> (Self, u32) {
+        let cap = CAP as u32;
+        let current = self.0 as u32;
+        let full_turns = amount.unsigned_abs() / cap;
+        let net_amount = amount.unsigned_abs() % cap;
+        let (next, passes_zero) = if amount >= 0 {
+            ((current + net_amount) % cap, current + net_amount >= cap)
+        } else {
+            ((current + cap - net_amount) % cap, net_amount >= current)
+        };
+        let wraps = full_turns + (current != 0 && passes_zero) as u32;
+        (Self(next as u16), wraps)
+    }
+}
+
 #[derive(Debug)]
 pub struct Annotate<T, A> {
     pub value: T,
@@ -612,8 +2593,42 @@ where
     }
 }
 
+impl<T, A> Annotate<T, A> {
+    /// Attaches a second annotation next to the existing one. Ordering still
+    /// only depends on the value.
+    #[must_use]
+    pub fn and<B>(self, extra: B) -> Annotate<T, (A, B)> {
+        Annotate {
+            value: self.value,
+            annotation: (self.annotation, extra),
+        }
+    }
+
+    /// Transforms the annotation, keeping the value (and so the ordering).
+    #[must_use]
+    pub fn map_annotation<B>(self, f: impl FnOnce(A) -> B) -> Annotate<T, B> {
+        Annotate {
+            value: self.value,
+            annotation: f(self.annotation),
+        }
+    }
+
+    #[must_use]
+    pub const fn value(&self) -> &T {
+        &self.value
+    }
+
+    #[must_use]
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
 pub trait AnnotateExt<T, A> {
     fn annotate(self, annotation: A) -> Annotate<T, A>;
+    /// Like [`AnnotateExt::annotate`], but borrows the value instead of moving
+    /// it.
+    fn annotate_ref(&self, annotation: A) -> Annotate<&T, A>;
 }
 
 impl<T, A> AnnotateExt<T, A> for T {
@@ -623,4 +2638,23 @@ impl<T, A> AnnotateExt<T, A> for T {
             annotation,
         }
     }
+
+    fn annotate_ref(&self, annotation: A) -> Annotate<&T, A> {
+        Annotate {
+            value: self,
+            annotation,
+        }
+    }
+}
+
+/// Consumes a heap of annotated values and returns just the annotations,
+/// sorted by ascending value.
+#[must_use]
+pub fn drain_sorted_annotations<T: Ord, A>(
+    heap: std::collections::BinaryHeap<Annotate<T, A>>,
+) -> Vec<A> {
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|annotated| annotated.annotation)
+        .collect()
 }
@@ -0,0 +1,124 @@
+//! Minimal SVG output for looking at debug visuals of a puzzle.
+//!
+//! Shapes are written in user coordinates, which the `viewBox` maps onto the
+//! figure. Styling is left to the caller: each shape takes its extra
+//! attributes (e.g. `fill="red"`) as a string that is written verbatim.
+
+use std::fmt::{self, Display, Write as _};
+
+/// An SVG document built one shape at a time.
+#[derive(Debug, Clone)]
+pub struct SvgCanvas {
+    view_box: (f64, f64, f64, f64),
+    figure_width: f64,
+    elements: String,
+}
+
+impl SvgCanvas {
+    /// Creates an empty canvas showing the `width` x `height` area starting
+    /// at `(min_x, min_y)`. The figure is `figure_width` pixels wide, and as
+    /// tall as needed to keep the aspect ratio.
+    #[must_use]
+    pub fn new(min_x: f64, min_y: f64, width: f64, height: f64, figure_width: f64) -> Self {
+        Self {
+            view_box: (min_x, min_y, width, height),
+            figure_width,
+            elements: String::new(),
+        }
+    }
+
+    /// A closed polygon through `points`.
+    pub fn polygon(
+        &mut self,
+        points: impl IntoIterator<Item = (f64, f64)>,
+        attributes: &str,
+    ) -> &mut Self {
+        self.elements.push_str("<polygon points=\"");
+        for (x, y) in points {
+            write!(self.elements, "{x},{y} ").unwrap();
+        }
+        writeln!(self.elements, "\" {attributes} />").unwrap();
+        self
+    }
+
+    /// A rectangle with its top-left corner at `(x, y)`.
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, attributes: &str) -> &mut Self {
+        writeln!(
+            self.elements,
+            "<rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{height}\" {attributes}/>"
+        )
+        .unwrap();
+        self
+    }
+
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64), attributes: &str) -> &mut Self {
+        let ((x1, y1), (x2, y2)) = (from, to);
+        writeln!(
+            self.elements,
+            "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" {attributes}/>"
+        )
+        .unwrap();
+        self
+    }
+
+    pub fn circle(&mut self, center: (f64, f64), radius: f64, attributes: &str) -> &mut Self {
+        let (cx, cy) = center;
+        writeln!(
+            self.elements,
+            "<circle cx=\"{cx}\" cy=\"{cy}\" r=\"{radius}\" {attributes}/>"
+        )
+        .unwrap();
+        self
+    }
+
+    /// Writes the whole document.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from the writer.
+    pub fn write_to(&self, mut w: impl std::io::Write) -> std::io::Result<()> {
+        write!(w, "{self}")
+    }
+}
+
+impl Display for SvgCanvas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (min_x, min_y, width, height) = self.view_box;
+        let figure_height = self.figure_width * height / width;
+        writeln!(
+            f,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{figure_height}\" viewBox=\"{min_x} {min_y} {width} {height}\">",
+            self.figure_width
+        )?;
+        f.write_str(&self.elements)?;
+        f.write_str("</svg>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render() {
+        let mut canvas = SvgCanvas::new(0.0, 0.0, 10.0, 5.0, 100.0);
+        canvas
+            .polygon([(0.0, 0.0), (4.0, 0.0), (2.0, 3.5)], "fill=\"blue\"")
+            .rect(1.0, 1.0, 2.0, 0.5, "fill=\"red\"")
+            .line((0.0, 5.0), (10.0, 0.0), "stroke=\"black\"")
+            .circle((7.0, 2.5), 1.5, "fill=\"none\"");
+        let svg = canvas.to_string();
+        assert_eq!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"50\" viewBox=\"0 0 10 5\">\n\
+             <polygon points=\"0,0 4,0 2,3.5 \" fill=\"blue\" />\n\
+             <rect x=\"1\" y=\"1\" width=\"2\" height=\"0.5\" fill=\"red\"/>\n\
+             <line x1=\"0\" y1=\"5\" x2=\"10\" y2=\"0\" stroke=\"black\"/>\n\
+             <circle cx=\"7\" cy=\"2.5\" r=\"1.5\" fill=\"none\"/>\n\
+             </svg>"
+        );
+        let mut written = Vec::new();
+        canvas.write_to(&mut written).unwrap();
+        assert_eq!(written, svg.as_bytes());
+    }
+}
@@ -1,7 +1,9 @@
-use std::{collections::VecDeque, fmt::Display, str::FromStr};
+use std::{fmt::Display, str::FromStr};
 
 use aoc_runner_derive::{aoc, aoc_generator};
-use aoc_utils::{NumberExt, example_tests, known_input_tests};
+use aoc_utils::{
+    BitRow, NumberExt, example_tests, known_input_tests, min_steps_to_dense, set_bits, solve_gf2,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct Pattern(u16);
@@ -13,15 +15,10 @@ impl Pattern {
 
     fn from_machine_config_string(input: &str) -> (Self, u8) {
         // [.###.#] = Pattern(0b011101)
-        let mut bits = 0;
-        let mut bit_count = 0;
         let input = input.strip_prefix('[').unwrap();
         let input = input.strip_suffix(']').unwrap();
-        for c in input.chars() {
-            bits = bits << 1 | ((c == '#') as u16);
-            bit_count += 1;
-        }
-        (Pattern(bits), bit_count)
+        let row = BitRow::<u16>::from_hashes_string(input).unwrap();
+        (Pattern(row.bits()), row.width() as u8)
     }
 
     fn from_button_wiring_string(input: &str, bit_count: u8) -> Self {
@@ -39,16 +36,14 @@ impl Pattern {
         Self(self.0 ^ button.0)
     }
 
-    fn as_index(self) -> usize {
-        self.0.into()
+    fn to_string(self, bit_count: u8) -> String {
+        BitRow::<u16>::from_bits(self.0, bit_count.into()).to_string()
     }
+}
 
-    fn to_string(self, bit_count: u8) -> String {
-        let mut s = String::new();
-        for i in (0..bit_count).rev() {
-            s.push(if self.0 & (1 << i) != 0 { '#' } else { '.' });
-        }
-        s
+impl From<Pattern> for usize {
+    fn from(pattern: Pattern) -> Self {
+        pattern.0.into()
     }
 }
 
@@ -115,42 +110,44 @@ fn parse(input: &str) -> Vec<Machine> {
 }
 
 fn find_min_clicks_to_switch_on(machine: &Machine) -> usize {
-    let mut queue = VecDeque::new();
-    let mut visited = [false; 1024];
-    queue.push_back((Pattern::all_zero(), 0));
-    visited[Pattern::all_zero().as_index()] = true;
-
     println!(
         "+ Starting search; expected: {}",
         machine.expected_state.to_string(machine.bit_count)
     );
-    while let Some((state, clicks)) = queue.pop_front() {
-        println!(
-            "State: {}; clicks: {clicks}",
-            state.to_string(machine.bit_count)
-        );
-        if state == machine.expected_state {
-            return clicks;
-        }
-        for &button in &machine.buttons {
-            let next_state = state.click(button);
-            let next_clicks = clicks + 1;
-            if next_state == machine.expected_state {
-                println!("Found solution! {next_clicks} clicks");
-                return next_clicks;
-            }
-            if !visited[next_state.as_index()] {
-                visited[next_state.as_index()] = true;
-                queue.push_back((next_state, next_clicks));
-            }
-        }
-    }
-
-    panic!("No solution found");
+    let clicks = min_steps_to_dense::<1024, _, _, _>(
+        Pattern::all_zero(),
+        &machine.expected_state,
+        |&state| {
+            machine
+                .buttons
+                .iter()
+                .map(move |&button| state.click(button))
+        },
+    )
+    .expect("No solution found");
+    println!("Found solution! {clicks} clicks");
+    clicks as usize
 }
 
 #[aoc(day10, part1)]
 fn part1(input: &[Machine]) -> usize {
+    input
+        .iter()
+        .map(|machine| {
+            let buttons = machine
+                .buttons
+                .iter()
+                .map(|b| b.0.into())
+                .collect::<Vec<_>>();
+            let target = machine.expected_state.0.into();
+            solve_gf2(&buttons, target, machine.bit_count.into()).expect("No solution found")
+                as usize
+        })
+        .sum()
+}
+
+#[aoc(day10, part1, bfs)]
+fn part1_bfs(input: &[Machine]) -> usize {
     input.iter().map(find_min_clicks_to_switch_on).sum()
 }
 
@@ -317,7 +314,7 @@ impl NumberExt for Fraction {
     fn parity(self) -> aoc_utils::Parity {
         todo!()
     }
-    fn split_odd_even(self) -> (Self, Self) {
+    fn halves(self) -> (Self, Self) {
         todo!()
     }
     fn zero() -> Self {
@@ -472,10 +469,12 @@ fn find_min_clicks_to_setup_joltage(machine: &Machine) -> usize {
     let cols = u8::try_from(machine.buttons.len()).unwrap() + 1;
     let mut x = Matrix::<Fraction>::new(rows, cols);
     for (col, button) in machine.buttons.iter().enumerate() {
-        for row in (0..rows).rev() {
-            if button.0 & (1 << row) != 0 {
-                x.set(row, col.try_into().unwrap(), Fraction::one());
-            }
+        for row in set_bits(button.0.into()) {
+            x.set(
+                row.try_into().unwrap(),
+                col.try_into().unwrap(),
+                Fraction::one(),
+            );
         }
     }
     for row in 0..rows {
@@ -703,11 +702,13 @@ example_tests! {
     [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}
     ",
     part1 => 7,
+    part1_bfs => 7,
     part2 => 33,
 }
 
 known_input_tests! {
     input: include_str!("../input/2025/day10.txt"),
     part1 => 500,
+    part1_bfs => 500,
     // part2 => 19763, // too slow for debug mode :(
 }
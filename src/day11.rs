@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use aoc_runner_derive::{aoc, aoc_generator};
-use aoc_utils::{AsciiUtils, example_tests, known_input_tests};
+use aoc_utils::{AsciiUtils, count_paths_dense, example_tests, known_input_tests};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct Label(u16);
@@ -12,9 +12,11 @@ impl Label {
     const SVR: Label = Label(2);
     const DAC: Label = Label(3);
     const FFT: Label = Label(4);
+}
 
-    fn as_index(self) -> usize {
-        self.0 as usize
+impl From<Label> for usize {
+    fn from(label: Label) -> usize {
+        label.0.into()
     }
 }
 
@@ -54,33 +56,7 @@ fn parse(input: &[u8]) -> Vec<Node> {
 }
 
 fn count_paths_between(edges: &HashMap<Label, Box<[Label]>>, start: Label, end: Label) -> u64 {
-    const MAX_LABELS: usize = 600;
-
-    fn recurse(
-        edges: &HashMap<Label, Box<[Label]>>,
-        start: Label,
-        end: Label,
-        counts: &mut [u32; MAX_LABELS],
-    ) -> u32 {
-        debug_assert_eq!(counts[start.as_index()], u32::MAX);
-        let mut count = 0;
-        if let Some(children) = edges.get(&start) {
-            for child in children {
-                if *child == end {
-                    count += 1;
-                } else if counts[child.as_index()] != u32::MAX {
-                    count += counts[child.as_index()];
-                } else {
-                    count += recurse(edges, *child, end, counts);
-                }
-            }
-        }
-        *(counts.get_mut(start.as_index()).unwrap()) = count;
-        count
-    }
-
-    let mut counts = [u32::MAX; MAX_LABELS];
-    recurse(edges, start, end, &mut counts).into()
+    count_paths_dense(edges, start, end).expect("the graph should be acyclic")
 }
 
 #[aoc(day11, part1)]
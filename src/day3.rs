@@ -1,5 +1,5 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use aoc_utils::{AsciiUtils, FromGridLike, example_tests, known_input_tests};
+use aoc_utils::{AsciiUtils, FromGridLike, SliceUtils, example_tests, known_input_tests};
 
 struct Banks {
     width: usize,
@@ -29,12 +29,9 @@ fn part1(input: &Banks) -> u64 {
     let mut total = 0;
     for row in input.rows() {
         let len = row.len();
-        let (first, first_byte) = row[..len - 1]
-            .iter()
-            .enumerate()
-            .rev()
-            .max_by_key(|(_, value)| **value)
-            .unwrap();
+        // leftmost maximum, so the second digit has as many options as possible
+        let first = row[..len - 1].arg_max_first().unwrap();
+        let first_byte = row[first];
         let second_byte = row[first + 1..].iter().max().unwrap();
         let first_value = first_byte - b'0';
         let second_value = second_byte - b'0';
@@ -1,5 +1,5 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use aoc_utils::{AsciiUtils, FromGridLike, example_tests, grid_cell_enum, known_input_tests};
+use aoc_utils::{AsciiUtils, Grid, example_tests, grid_cell_enum, known_input_tests};
 
 grid_cell_enum! {
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -9,138 +9,75 @@ grid_cell_enum! {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Position {
-    x: usize,
-    y: usize,
+#[aoc_generator(day4)]
+fn parse(input: &[u8]) -> Grid<Cell> {
+    input.grid_like().unwrap().into_grid()
 }
 
-fn position(x: usize, y: usize) -> Position {
-    Position { x, y }
+/// A roll of paper can be reached if fewer than four of its eight neighbors
+/// are also rolls.
+fn is_accessible(grid: &Grid<Cell>, x: usize, y: usize) -> bool {
+    grid[(x, y)] == Cell::Occupied
+        && grid
+            .neighbors8_matching(x, y, |&cell| cell == Cell::Occupied)
+            .count()
+            < 4
 }
 
-impl Position {
-    fn add(self, dx: isize, dy: isize) -> Option<Position> {
-        let x = self.x.checked_add_signed(dx)?;
-        let y = self.y.checked_add_signed(dy)?;
-        Some(position(x, y))
-    }
-    fn neighbors(self) -> impl Iterator<Item = Position> {
-        [
-            (-1, -1),
-            (-1, 0),
-            (-1, 1),
-            (0, -1),
-            (0, 1),
-            (1, -1),
-            (1, 0),
-            (1, 1),
-        ]
-        .into_iter()
-        .filter_map(move |(dx, dy)| self.add(dx, dy))
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Map {
-    width: usize,
-    height: usize,
-    cells: Vec<Cell>,
+#[aoc(day4, part1)]
+fn part1(input: &Grid<Cell>) -> usize {
+    (0..input.height())
+        .flat_map(|y| (0..input.width()).map(move |x| (x, y)))
+        .filter(|&(x, y)| is_accessible(input, x, y))
+        .count()
 }
 
-impl Map {
-    fn cell(&self, pos: Position) -> Option<Cell> {
-        if pos.x < self.width && pos.y < self.height {
-            self.cells.get(pos.y * self.width + pos.x).copied()
-        } else {
-            None
-        }
-    }
-
-    fn neighbors(&self, pos: Position) -> impl Iterator<Item = (Position, Cell)> {
-        pos.neighbors()
-            .filter_map(move |neighbor| Some((neighbor, self.cell(neighbor)?)))
-    }
-
-    fn cells(&self) -> impl Iterator<Item = (Position, Cell)> {
-        (0..=self.height).flat_map(move |y| {
-            (0..=self.width).filter_map(move |x| Some((position(x, y), self.cell(position(x, y))?)))
-        })
-    }
-
-    fn remove(&mut self, positions: impl Iterator<Item = Position>) -> Result<(), ()> {
-        for pos in positions {
-            if let Some(index) = pos
-                .y
-                .checked_mul(self.width)
-                .and_then(|i| i.checked_add(pos.x))
-            {
-                assert!(self.cells[index] == Cell::Occupied);
-                self.cells[index] = Cell::Empty;
-            } else {
-                return Err(());
-            }
-        }
-        Ok(())
-    }
+#[aoc(day4, part2)]
+fn part2(input: &Grid<Cell>) -> usize {
+    input.clone().erode_until_stable(Cell::Empty, is_accessible)
 }
 
-impl FromGridLike for Map {
-    type Cell = Cell;
-
-    fn from_cells(cells: Vec<Self::Cell>, width: usize, height: usize) -> Self {
-        Map {
-            width,
-            height,
-            cells,
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn occupied_neighbors() {
+        let grid = parse(b"..@@.\n@@@.@\n@@@@@");
+        let occupied = |x, y| {
+            grid.neighbors8_matching(x, y, |&cell| cell == Cell::Occupied)
+                .count()
+        };
+        assert_eq!(occupied(1, 1), 6);
+        assert_eq!(occupied(3, 0), 3);
+        assert_eq!(occupied(4, 2), 2);
+        assert!(is_accessible(&grid, 3, 0));
+        assert!(!is_accessible(&grid, 1, 1));
+        // empty cells are never accessible
+        assert!(!is_accessible(&grid, 0, 0));
     }
-}
 
-#[aoc_generator(day4)]
-fn parse(input: &[u8]) -> Map {
-    input.grid_like().unwrap().into_grid()
-}
-
-#[aoc(day4, part1)]
-fn part1(input: &Map) -> usize {
-    input
-        .cells()
-        .filter(|&(pos, cell)| {
-            cell == Cell::Occupied
-                && input
-                    .neighbors(pos)
-                    .filter(|&(_, cell)| cell == Cell::Occupied)
-                    .count()
-                    < 4
-        })
-        .count()
-}
-
-#[aoc(day4, part2)]
-fn part2(input: &Map) -> usize {
-    let mut removed_count = 0;
-    let mut map = input.clone();
-    loop {
-        let removable = map
-            .cells()
-            .filter(|&(pos, cell)| {
-                cell == Cell::Occupied
-                    && map
-                        .neighbors(pos)
-                        .filter(|&(_, cell)| cell == Cell::Occupied)
-                        .count()
-                        < 4
-            })
-            .map(|(pos, _)| pos)
-            .collect::<Vec<_>>();
-        if removable.is_empty() {
-            break;
-        }
-        removed_count += removable.len();
-        map.remove(removable.into_iter()).unwrap();
+    #[test]
+    fn nested_vec_grid() {
+        let input = aoc_utils::unindent_bytes(
+            b"
+            ..@@.@@@@.
+            @@@.@.@.@@
+            @@@@@.@.@@
+            ",
+        );
+        let grid = input
+            .as_slice()
+            .grid_like::<Cell>()
+            .unwrap()
+            .into_grid::<Vec<Vec<Cell>>>();
+        assert_eq!(grid.len(), 3);
+        assert!(grid.iter().all(|row| row.len() == 10));
+        assert_eq!(grid[0][2], Cell::Occupied);
+        assert_eq!(grid[1][3], Cell::Empty);
+        let column = grid.iter().map(|row| row[5]).collect::<Vec<_>>();
+        assert_eq!(column, [Cell::Occupied, Cell::Empty, Cell::Empty]);
     }
-    removed_count
 }
 
 example_tests! {
@@ -1,63 +1,63 @@
+use std::ops::RangeInclusive;
+
 use aoc_runner_derive::{aoc, aoc_generator};
-use aoc_utils::{Interval, example_tests, known_input_tests};
+use aoc_utils::{AsciiUtils, RangeSet, Successor, example_tests, known_input_tests, len_inclusive};
 
 #[derive(Debug, Clone)]
 struct Input {
-    intervals: Vec<Interval<u64>>,
+    ranges: Vec<RangeInclusive<u64>>,
     ids: Vec<u64>,
 }
 
 #[aoc_generator(day5)]
-fn parse(input: &str) -> Input {
-    let mut lines = input.lines();
-    let mut intervals = Vec::new();
-    let mut ids = Vec::new();
-
-    for line in &mut lines {
-        if line.is_empty() {
-            break;
-        }
-        let (start, end) = line.split_once('-').unwrap();
-        let start = start.parse().unwrap();
-        let end = end.parse().unwrap();
-        intervals.push((start..=end).try_into().unwrap());
-    }
-
-    for line in lines {
-        ids.push(line.parse().unwrap());
-    }
-
-    Input { intervals, ids }
+fn parse(input: &[u8]) -> Input {
+    let mut sections = input.sections();
+    let ranges = sections
+        .next()
+        .unwrap()
+        .ascii_lines()
+        .map(|line| {
+            let (start, end) = line.parse_pair(b'-').unwrap();
+            start..=end
+        })
+        .collect();
+    let ids = sections
+        .next()
+        .unwrap()
+        .ascii_lines()
+        .map(|line| line.parse().unwrap())
+        .collect();
+    Input { ranges, ids }
 }
 
 #[aoc(day5, part1)]
 fn part1(input: &Input) -> usize {
-    input
-        .ids
-        .iter()
-        .filter(|&&id| input.intervals.iter().any(|interval| interval.contains(id)))
-        .count()
+    let fresh = RangeSet::from_unsorted(input.ranges.clone());
+    input.ids.iter().filter(|&&id| fresh.contains(id)).count()
 }
 
 #[aoc(day5, part2)]
-fn part2(input: &Input) -> u64 {
-    let mut intervals = input.intervals.clone();
-    intervals.sort_unstable_by_key(Interval::start);
-    sum_intervals_overlapping(&intervals)
+fn part2(input: &Input) -> u128 {
+    let mut ranges = input.ranges.clone();
+    ranges.sort_unstable_by_key(|range| *range.start());
+    sum_intervals_overlapping(&ranges)
 }
 
-fn sum_intervals_overlapping(intervals: &[Interval<u64>]) -> u64 {
-    debug_assert!(intervals.is_sorted_by_key(Interval::start));
+// the count is a u128 because a range can cover every u64
+fn sum_intervals_overlapping(ranges: &[RangeInclusive<u64>]) -> u128 {
+    debug_assert!(ranges.is_sorted_by_key(|range| *range.start()));
     let mut result = 0;
-    let mut last_end = 0;
-    for interval in intervals {
-        let non_overlapping_part = if interval.start() <= last_end {
-            (interval.end()).saturating_sub(last_end)
-        } else {
-            (interval.end()) - interval.start()
+    // the first value not yet counted, or None once we counted u64::MAX
+    let mut next_uncounted = Some(0);
+    for range in ranges {
+        let Some(next) = next_uncounted else {
+            break;
         };
-        result += non_overlapping_part;
-        last_end = last_end.max(interval.end());
+        let start = next.max(*range.start());
+        result += len_inclusive(&(start..=*range.end()));
+        if *range.end() >= next {
+            next_uncounted = range.end().successor();
+        }
     }
     result
 }
@@ -68,21 +68,38 @@ mod tests {
 
     #[test]
     fn test_sum_intervals_non_overlapping() {
-        let intervals = [1..4, 4..8, 9..12].map(Into::into);
-        assert_eq!(sum_intervals_overlapping(&intervals), 3 + 4 + 3);
+        let ranges = [1..=3, 4..=7, 9..=11];
+        assert_eq!(sum_intervals_overlapping(&ranges), 3 + 4 + 3);
     }
 
     #[test]
     fn test_sum_intervals_overlapping() {
-        let intervals = [1..11, 5..16].map(Into::into);
-        assert_eq!(sum_intervals_overlapping(&intervals), 10 + 5);
-        let intervals = [1..11, 5..16, 21..31].map(Into::into);
-        assert_eq!(sum_intervals_overlapping(&intervals), 10 + 5 + 10);
+        let ranges = [1..=10, 5..=15];
+        assert_eq!(sum_intervals_overlapping(&ranges), 10 + 5);
+        let ranges = [1..=10, 5..=15, 21..=30];
+        assert_eq!(sum_intervals_overlapping(&ranges), 10 + 5 + 10);
+    }
+
+    #[test]
+    fn test_sum_intervals_up_to_max() {
+        let ranges = [0..=u64::MAX];
+        assert_eq!(sum_intervals_overlapping(&ranges), 1 << 64);
+        let ranges = [0..=9, 5..=u64::MAX, u64::MAX..=u64::MAX];
+        assert_eq!(sum_intervals_overlapping(&ranges), 1 << 64);
+        let input = parse(b"0-18446744073709551615\n\n0\n");
+        assert_eq!(part2(&input), 1 << 64);
+    }
+
+    #[test]
+    fn parse_crlf() {
+        let input = parse(b"3-5\r\n10-14\r\n\r\n1\r\n5\r\n");
+        assert_eq!(input.ranges, [3..=5, 10..=14]);
+        assert_eq!(input.ids, [1, 5]);
     }
 }
 
 example_tests! {
-    "
+    b"
     3-5
     10-14
     16-20
@@ -100,7 +117,7 @@ example_tests! {
 }
 
 known_input_tests! {
-    input: include_str!("../input/2025/day5.txt"),
+    input: include_bytes!("../input/2025/day5.txt"),
     part1 => 638,
     part2 => 352946349407338,
 }
@@ -6,7 +6,9 @@ use std::{
 };
 
 use aoc_runner_derive::{aoc, aoc_generator};
-use aoc_utils::{Annotate, AnnotateExt, example_tests, known_input_tests};
+use aoc_utils::{
+    Annotate, AnnotateExt, SliceUtils, drain_sorted_annotations, example_tests, known_input_tests,
+};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 struct Pos {
@@ -25,13 +27,10 @@ impl Pos {
     }
 
     fn squared_distance(self, other: Pos) -> u64 {
-        let x1: i64 = self.x.into();
-        let y1: i64 = self.y.into();
-        let z1: i64 = self.z.into();
-        let x2: i64 = other.x.into();
-        let y2: i64 = other.y.into();
-        let z2: i64 = other.z.into();
-        ((x1 - x2).pow(2) + (y1 - y2).pow(2) + (z1 - z2).pow(2)).cast_unsigned()
+        let dx = u64::from(self.x.abs_diff(other.x));
+        let dy = u64::from(self.y.abs_diff(other.y));
+        let dz = u64::from(self.z.abs_diff(other.z));
+        dx * dx + dy * dy + dz * dz
     }
 }
 
@@ -102,7 +101,7 @@ fn parse(input: &str) -> Vec<Pos> {
     input.lines().map(|line| line.parse().unwrap()).collect()
 }
 
-fn find_n_closest_links(nodes: &[Pos], n: usize) -> Vec<Annotate<u64, (Pos, Pos)>> {
+fn find_n_closest_links(nodes: &[Pos], n: usize) -> Vec<(Pos, Pos)> {
     let mut top_n = BinaryHeap::with_capacity(n);
     for (i, &a) in nodes.iter().enumerate() {
         for &b in &nodes[i + 1..] {
@@ -119,7 +118,7 @@ fn find_n_closest_links(nodes: &[Pos], n: usize) -> Vec<Annotate<u64, (Pos, Pos)
             }
         }
     }
-    top_n.into_sorted_vec()
+    drain_sorted_annotations(top_n)
 }
 
 #[aoc(day8, part1)]
@@ -131,27 +130,19 @@ fn part1(input: &[Pos]) -> usize {
         // real input data
         1000
     };
-    let top_n = find_n_closest_links(input, n);
+    largest_circuits_product(input, n)
+}
+
+fn largest_circuits_product(input: &[Pos], n: usize) -> usize {
+    let edges = find_n_closest_links(input, n);
 
     if cfg!(debug_assertions) {
-        for Annotate {
-            annotation: (a, b),
-            value: distance,
-        } in &top_n
-        {
+        for &(a, b) in &edges {
+            let distance = a.squared_distance(b);
             println!("{a:11}   {b:11}  d = {distance}");
         }
     }
 
-    let edges = top_n
-        .iter()
-        .map(
-            |Annotate {
-                 annotation: (a, b), ..
-             }| (*a, *b),
-        )
-        .collect::<Vec<_>>();
-
     let nodes = edges
         .iter()
         .flat_map(|&(a, b)| [a, b])
@@ -223,9 +214,9 @@ fn part2_small(nodes: &[PackedPos]) -> u64 {
 
 fn part2<const SIZE: usize, const EDGES: usize>(nodes: &[PackedPos]) -> u64 {
     // let start = Instant::now();
-    let mut pairs = BinaryHeap::with_capacity(nodes.len() * (nodes.len() - 1) / 2);
+    let mut pairs = BinaryHeap::with_capacity(nodes.pairs().len());
 
-    // let mut pairs = aoc_utils::BinaryHeap::<EDGES, _>::new();
+    // let mut pairs = aoc_utils::ArrayHeap::<EDGES, _>::new();
     let mut forest = [Node { parent: 0, size: 1 }; SIZE];
 
     for (idx_a, &a) in nodes.iter().enumerate() {
@@ -326,9 +317,10 @@ fn part2_aa<const SIZE: usize, const EDGES: usize>(pos: &[PackedPos]) -> i64 {
         }
     }
 
+    // there are more edges than fit in a u16 for the real input
     let mut indices = vec![0; EDGES];
     for i in 0..EDGES {
-        indices[i] = i as u16;
+        indices[i] = i as u32;
     }
     indices.sort_unstable_by_key(|&i| dists[i as usize]);
 
@@ -342,7 +334,8 @@ fn part2_aa<const SIZE: usize, const EDGES: usize>(pos: &[PackedPos]) -> i64 {
         let v = find_set(j, &mut forest);
         if u != v {
             union_sets(u, v, &mut forest);
-            if forest[v].size == SIZE {
+            // either u or v can end up as the root of the merged set
+            if forest[find_set(u, &mut forest)].size == SIZE {
                 result = (pos[i].x() as i64 * pos[j].x() as i64) as i64;
                 break;
             }
@@ -378,6 +371,9 @@ example_tests! {
     parser: super::parse,
     part1 => 40,
 
+    parser: super::parse,
+    largest_circuits_product(10) => 40,
+
     parser: super::parse_part2,
     part2_small => 25272,
 
@@ -387,12 +383,12 @@ example_tests! {
 
 known_input_tests! {
     input: include_str!("../input/2025/day8.txt"),
+    // part2_aa keeps all the edges in arrays on the stack
+    stack_size: 64 << 20,
     parser: super::parse,
     part1 => 244188,
     parser: super::parse_part2,
     part2_big => 8361881885,
-
-    // disabled because it overflows the stack
-    // parser: super::parse_part2,
-    // part2_aa_big => 8361881885,
+    parser: super::parse_part2,
+    part2_aa_big => 8361881885,
 }
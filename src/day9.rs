@@ -1,7 +1,10 @@
 use std::cmp::Reverse;
 
 use aoc_runner_derive::{aoc, aoc_generator};
-use aoc_utils::{SliceUtils, example_tests, known_input_tests};
+use aoc_utils::{
+    Point2, Segment, SliceUtils, Winding, example_tests, intersects, known_input_tests, trace,
+    winding,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct Pos {
@@ -115,25 +118,28 @@ fn part2(input: &[Pos]) -> u64 {
     // one extreme of the polygon (e.g. one of the topmost points) and decide
     // that it has to live on the smallest y coordinate, and follow the loop
     // from there.
-    let (topmost_index, topmost_y) = input
+    let loop_points = input
         .iter()
-        .map(|p| p.y)
-        .enumerate()
-        .min_by_key(|&(_, y)| y)
-        .unwrap();
+        .map(|p| Point2::new(i64::from(p.x), i64::from(p.y)))
+        .collect::<Vec<_>>();
+    // 1 means going forward through the points, -1 backwards, so that we
+    // always follow the loop clockwise.
+    let direction: isize = match winding(&loop_points) {
+        Winding::Clockwise => 1,
+        Winding::CounterClockwise => -1,
+    };
+    let next_index = |index: usize| {
+        index
+            .checked_add_signed(direction)
+            .unwrap_or(input.len() - 1)
+            % input.len()
+    };
 
-    // Identify the next point that forms a horizontal segment together with the
-    // already identified topmost point. It could be either the one before or
-    // after. Note that 1 implies clockwise, -1 implies counter-clockwise.
-    let direction = [-1, 1]
-        .into_iter()
-        .find(|&dx| {
-            let next_index = topmost_index
-                .checked_add_signed(dx)
-                .unwrap_or(input.len() - 1)
-                % input.len();
-            topmost_y == input[next_index].y
-        })
+    // Find the start of a horizontal segment at the top. Since we're going
+    // clockwise, we're heading right along it.
+    let topmost_y = input.iter().map(|p| p.y).min().unwrap();
+    let topmost_index = (0..input.len())
+        .find(|&i| input[i].y == topmost_y && input[next_index(i)].y == topmost_y)
         .unwrap();
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -153,35 +159,16 @@ fn part2(input: &[Pos]) -> u64 {
                 Heading::Up => Heading::Right,
             }
         }
-        const fn opposite(self) -> Self {
-            match self {
-                Heading::Right => Heading::Left,
-                Heading::Down => Heading::Up,
-                Heading::Left => Heading::Right,
-                Heading::Up => Heading::Down,
-            }
-        }
-        const fn counter_clockwise(self) -> Self {
-            self.clockwise().opposite()
-        }
     }
 
     let mut transformed_points = Vec::<Pos>::new();
     // The first corner we consider is actually the next one after the topmost
     // point we found, so that we can make use of the information of the
     // current heading.
-    let start_idx = topmost_index
-        .checked_add_signed(direction)
-        .unwrap_or(input.len() - 1)
-        % input.len();
-    let mut heading = if direction == 1 {
-        Heading::Right
-    } else {
-        Heading::Left
-    };
-    for i in 0..input.len() {
-        let idx = (start_idx + i) % input.len();
-        let next_idx = idx.checked_add_signed(direction).unwrap_or(input.len() - 1) % input.len();
+    let mut idx = next_index(topmost_index);
+    let mut heading = Heading::Right;
+    for _ in 0..input.len() {
+        let next_idx = next_index(idx);
 
         let point = input[idx];
         let next_point = input[next_idx];
@@ -225,12 +212,7 @@ fn part2(input: &[Pos]) -> u64 {
                 Heading::Left
             }
         };
-        let positive_heading = if direction == 1 {
-            heading.clockwise()
-        } else {
-            heading.counter_clockwise()
-        };
-        let is_positive = positive_heading == next_heading;
+        let is_positive = heading.clockwise() == next_heading;
         // This thing is full of symmetries to exploit but I can think about
         // it more explicitly if I just write down the whole table with all
         // the cases.
@@ -251,6 +233,7 @@ fn part2(input: &[Pos]) -> u64 {
         let transformed_point = Pos::new(point.x * 3 + dx, point.y * 3 + dy);
         transformed_points.push(transformed_point);
         heading = next_heading;
+        idx = next_idx;
     }
 
     let mut horizontal_segments = Vec::with_capacity(input.len());
@@ -265,8 +248,10 @@ fn part2(input: &[Pos]) -> u64 {
         if p1.x == p2.x {
             vertical_segments.push((p1, p2));
         } else {
-            debug_assert!(p1.y == p2.y);
-            horizontal_segments.push((p1, p2));
+            horizontal_segments.push(Segment::new(
+                Point2::new(p1.x, p1.y),
+                Point2::new(p2.x, p2.y),
+            ));
         }
     }
     // Longer segments are more likely to collide with the rectangle sides, and
@@ -275,7 +260,8 @@ fn part2(input: &[Pos]) -> u64 {
     // algorithm to the input data) for speed. We could just not do it, and the
     // problem would still be manageable. I leave the code here because it
     // doesn't hurt even in the general case.
-    horizontal_segments.sort_unstable_by_key(|(p1, p2)| Reverse(p1.x.abs_diff(p2.x)));
+    horizontal_segments
+        .sort_unstable_by_key(|segment| Reverse(segment.start.x.abs_diff(segment.end.x)));
     vertical_segments.sort_unstable_by_key(|(p1, p2)| Reverse(p1.y.abs_diff(p2.y)));
 
     // Previously I used to compute all the rectangle areas and put them in a
@@ -297,12 +283,10 @@ fn part2(input: &[Pos]) -> u64 {
         let x2 = pos1.x.max(pos2.x) * 3 + 1;
         let y1 = pos1.y.min(pos2.y) * 3 + 1;
         let y2 = pos1.y.max(pos2.y) * 3 + 1;
-        for &segment in &horizontal_segments {
-            let (Pos { x: ex1, y: ey }, Pos { x: ex2, .. }) = segment;
-            if x1 >= ex1 && x1 <= ex2 && ey >= y1 && ey <= y2 {
-                continue 'areas;
-            }
-            if x2 >= ex1 && x2 <= ex2 && ey >= y1 && ey <= y2 {
+        let left_side = Segment::new(Point2::new(x1, y1), Point2::new(x1, y2));
+        let right_side = Segment::new(Point2::new(x2, y1), Point2::new(x2, y2));
+        for segment in &horizontal_segments {
+            if intersects(&left_side, segment) || intersects(&right_side, segment) {
                 continue 'areas;
             }
         }
@@ -342,7 +326,7 @@ fn write_svg(
     input: &[Pos],
     transformed: &[Pos],
     rect: (Pos, Pos),
-    mut w: impl std::io::Write,
+    w: impl std::io::Write,
 ) -> std::io::Result<()> {
     let min_x = input.iter().map(|p| p.x).min().unwrap() as f64;
     let min_y = input.iter().map(|p| p.y).min().unwrap() as f64;
@@ -350,40 +334,24 @@ fn write_svg(
     let max_y = input.iter().map(|p| p.y).max().unwrap() as f64;
     let width = 4.0 + max_x - min_x;
     let height = 4.0 + max_y - min_y;
-    let figure_height = 1000.0 * height / width;
-    write!(
-        w,
-        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"1000\" height=\"{figure_height}\" viewBox=\"0 0 {width} {height}\">\n"
-    )?;
-    write!(w, "<polygon points=\"")?;
-    for pos in transformed {
-        let x = (pos.x - 1) as f64 / 3.0;
-        let y = (pos.y - 1) as f64 / 3.0;
-        write!(w, "{},{} ", x, y)?;
-    }
-    write!(
-        w,
-        "\" fill=\"blue\" stroke=\"green\" stroke-width=\"0.333333333\" />\n"
-    )?;
+    let mut canvas = aoc_utils::viz::SvgCanvas::new(0.0, 0.0, width, height, 1000.0);
+    canvas.polygon(
+        transformed
+            .iter()
+            .map(|pos| ((pos.x - 1) as f64 / 3.0, (pos.y - 1) as f64 / 3.0)),
+        "fill=\"blue\" stroke=\"green\" stroke-width=\"0.333333333\"",
+    );
     for pos in input {
-        let x = pos.x;
-        let y = pos.y;
-        write!(
-            w,
-            "<rect x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\" fill=\"#ffa0a070\"/>\n",
-            x = x as f64 - 0.5,
-            y = y as f64 - 0.5,
-            w = 1.0,
-            h = 1.0,
-        )?;
-        write!(
-            w,
-            "<rect x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\" fill=\"purple\"/>\n",
-            x = x as f64 - 0.16666666,
-            y = y as f64 - 0.16666666,
-            w = 0.333333,
-            h = 0.333333,
-        )?;
+        let x = pos.x as f64;
+        let y = pos.y as f64;
+        canvas.rect(x - 0.5, y - 0.5, 1.0, 1.0, "fill=\"#ffa0a070\"");
+        canvas.rect(
+            x - 0.16666666,
+            y - 0.16666666,
+            0.333333,
+            0.333333,
+            "fill=\"purple\"",
+        );
     }
     {
         // draw rectangle
@@ -391,12 +359,9 @@ fn write_svg(
         let ry = rect.0.y.min(rect.1.y) as f64;
         let rw = rect.1.x.abs_diff(rect.0.x) as f64;
         let rh = rect.1.y.abs_diff(rect.0.y) as f64;
-        write!(
-            w,
-            "<rect x=\"{rx}\" y=\"{ry}\" width=\"{rw}\" height=\"{rh}\" fill=\"#ff00007f\"/>\n",
-        )?;
+        canvas.rect(rx, ry, rw, rh, "fill=\"#ff00007f\"");
     }
-    write!(w, "</svg>")
+    canvas.write_to(w)
 }
 
 #[aoc(day9, part2, faster)]
@@ -415,7 +380,11 @@ fn part2_fast(tiles: &[Pos]) -> u64 {
         let [x2, y2] = tiles[(i + 1) % tiles.len()].unpack();
         sorted_edges.push((x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2)));
     }
-    sorted_edges.sort_unstable_by_key(|&(x1, y1, x2, y2)| Reverse((x2 - x1 + 1) + (y2 - y1 + 1)));
+    // ties are broken by position, so that the order is always the same
+    sorted_edges.sort_unstable_by_key(|&(x1, y1, x2, y2)| {
+        (Reverse((x2 - x1 + 1) + (y2 - y1 + 1)), x1, y1, x2, y2)
+    });
+    trace!("sorted_edges", sorted_edges);
 
     let mut best = 0;
     // let mut rectangles_tested = 0;
@@ -479,3 +448,35 @@ known_input_tests! {
     part2 => 1537458069,
     part2_fast => 1537458069,
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn part2_either_winding() {
+        let mut tiles = super::parse("7,1\n11,1\n11,7\n9,7\n9,5\n2,5\n2,3\n7,3\n");
+        assert_eq!(super::part2(&tiles), 24);
+        tiles.reverse();
+        assert_eq!(super::part2(&tiles), 24);
+        // start from a point that isn't on the top edge
+        tiles.rotate_left(3);
+        assert_eq!(super::part2(&tiles), 24);
+    }
+}
+
+#[cfg(all(test, feature = "trace"))]
+mod trace_tests {
+    #[test]
+    fn part2_fast_sorted_edges() {
+        let tiles = super::parse("7,1\n11,1\n11,7\n9,7\n9,5\n2,5\n2,3\n7,3\n");
+        let (_, traces) = aoc_utils::capture(|| super::part2_fast(&tiles));
+        // longest edges first, so that rectangles crossing the polygon are
+        // rejected early
+        assert_eq!(
+            traces.get("sorted_edges"),
+            Some(
+                "[(2, 5, 9, 5), (11, 1, 11, 7), (2, 3, 7, 3), (7, 1, 11, 1), \
+                 (2, 3, 2, 5), (7, 1, 7, 3), (9, 5, 9, 7), (9, 7, 11, 7)]"
+            )
+        );
+    }
+}